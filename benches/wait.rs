@@ -0,0 +1,95 @@
+//! Measures the latency of the common operations on the real device with criterion.
+//!
+//! Run it with `cargo bench`. Without `/dev/ntsync` every benchmark is skipped.
+//! `single/wait_any` and `single/wait_all` cover the waits on one object, which keep the one id on the stack instead of an [Vec].
+//! No numbers are recorded here, they depend on the machine and the kernel. To compare an change, run the benchmarks on the commit before and after it
+//! on the same machine, criterion reports the difference to the previous run.
+use std::{
+    collections::HashSet,
+    hint::black_box,
+};
+
+use criterion::{
+    BenchmarkId,
+    Criterion,
+};
+use ntsync::{
+    Error,
+    EventSources,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+};
+
+fn single_object(criterion: &mut Criterion, instance: &NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let mut group = criterion.benchmark_group("single");
+    group.bench_function("wait_any", |bencher| {
+        bencher.iter(|| -> Result<(), Error> {
+            event.signal()?;
+            black_box(instance.wait_any(HashSet::from([event.into()]), None, None, NtSyncFlags::empty(), None)?);
+            Ok(())
+        })
+    });
+    group.bench_function("wait_all", |bencher| {
+        bencher.iter(|| -> Result<(), Error> {
+            event.signal()?;
+            black_box(instance.wait_all(HashSet::from([event.into()]), None, None, NtSyncFlags::empty(), None)?);
+            Ok(())
+        })
+    });
+    group.finish();
+    event.delete()
+}
+
+fn many_objects(criterion: &mut Criterion, instance: &NtSync) -> Result<(), Error> {
+    let mut group = criterion.benchmark_group("wait_any");
+    for count in [
+        2, 8, 64,
+    ] {
+        let events = (0..count).map(|_| instance.new_event(false, true)).collect::<Result<Vec<_>, Error>>()?;
+        if let Some(last) = events.last() {
+            last.signal()?;
+        }
+        let sources: HashSet<EventSources> = events.iter().map(|event| EventSources::Event(*event)).collect();
+        group.bench_with_input(BenchmarkId::from_parameter(count), &sources, |bencher, sources| {
+            bencher.iter(|| black_box(instance.wait_any(sources.clone(), None, None, NtSyncFlags::empty(), None)))
+        });
+        for event in events {
+            event.delete()?;
+        }
+    }
+    group.finish();
+    Ok(())
+}
+
+#[cfg(semaphore)]
+fn semaphore_throughput(criterion: &mut Criterion, instance: &NtSync) -> Result<(), Error> {
+    let semaphore = instance.new_semaphore(1)?;
+    criterion.bench_function("semaphore acquire+release", |bencher| {
+        bencher.iter(|| -> Result<(), Error> {
+            black_box(instance.wait_any(HashSet::from([semaphore.into()]), None, None, NtSyncFlags::empty(), None)?);
+            semaphore.release(1)?;
+            Ok(())
+        })
+    });
+    semaphore.delete()
+}
+
+fn main() -> Result<(), Error> {
+    let instance = match NtSync::new() {
+        Ok(instance) => instance,
+        Err(Error::NotExist) => {
+            println!("/dev/ntsync does not exist, skipping the benchmarks");
+            return Ok(());
+        },
+        Err(error) => return Err(error),
+    };
+    let mut criterion = Criterion::default().configure_from_args();
+    single_object(&mut criterion, &instance)?;
+    many_objects(&mut criterion, &instance)?;
+    #[cfg(semaphore)]
+    semaphore_throughput(&mut criterion, &instance)?;
+    criterion.final_summary();
+    Ok(())
+}
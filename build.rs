@@ -5,6 +5,15 @@ fn main() {
         mutex: { all(target_os = "linux", feature = "mutex") },
         random: {all(target_os = "linux", feature = "random")},
         semaphore: {all(target_os = "linux", feature = "semaphore")},
+        metrics: {all(target_os = "linux", feature = "metrics")},
+        minimal: {all(target_os = "linux", any(feature = "minimal", not(feature = "nix")))},
+        use_nix: {feature = "nix"},
+        debug_event: {all(target_os = "linux", feature = "debug-event")},
+        debug_semaphore: {all(target_os = "linux", feature = "debug-semaphore")},
+        asynchronous: {all(target_os = "linux", feature = "async")},
+        serialize: {all(target_os = "linux", feature = "serde")},
+        record: {all(target_os = "linux", feature = "record")},
+        no_cold_hints: {feature = "no-cold-hints"},
         not_linux: { not(target_os="linux")},
     }
 }
@@ -0,0 +1,96 @@
+//! The numbers of the ioctl interface of `/dev/ntsync`, as defined in `include/uapi/linux/ntsync.h` of the kernel.
+//!
+//! The wrappers of this crate are declared with these constants, so they can be used for own raw ioctls or to verify the ABI of an kernel.
+//! The request numbers are computed like the `_IOR`, `_IOW` and `_IOWR` macros of the kernel from the size of the argument structs the crate passes.
+
+use std::mem::size_of;
+
+#[cfg(mutex)]
+use crate::mutex::MutexArgs;
+#[cfg(semaphore)]
+use crate::semaphore::SemaphoreArgs;
+use crate::{
+    event::EventArgs,
+    wait::WaitArgs,
+};
+
+/// The type of all ntsync ioctls, `'N'`.
+pub const MAGIC: u8 = b'N';
+
+/// The number of `NTSYNC_IOC_CREATE_SEM`, issued on the device.
+pub const CREATE_SEM: u8 = 0x80;
+/// The number of `NTSYNC_IOC_SEM_RELEASE`, issued on an semaphore.
+pub const SEM_RELEASE: u8 = 0x81;
+/// The number of `NTSYNC_IOC_WAIT_ANY`, issued on the device.
+pub const WAIT_ANY: u8 = 0x82;
+/// The number of `NTSYNC_IOC_WAIT_ALL`, issued on the device.
+pub const WAIT_ALL: u8 = 0x83;
+/// The number of `NTSYNC_IOC_CREATE_MUTEX`, issued on the device.
+pub const CREATE_MUTEX: u8 = 0x84;
+/// The number of `NTSYNC_IOC_MUTEX_UNLOCK`, issued on an mutex.
+pub const MUTEX_UNLOCK: u8 = 0x85;
+/// The number of `NTSYNC_IOC_MUTEX_KILL`, issued on an mutex.
+pub const MUTEX_KILL: u8 = 0x86;
+/// The number of `NTSYNC_IOC_CREATE_EVENT`, issued on the device.
+pub const CREATE_EVENT: u8 = 0x87;
+/// The number of `NTSYNC_IOC_EVENT_SET`, issued on an event.
+pub const EVENT_SET: u8 = 0x88;
+/// The number of `NTSYNC_IOC_EVENT_RESET`, issued on an event.
+pub const EVENT_RESET: u8 = 0x89;
+/// The number of `NTSYNC_IOC_EVENT_PULSE`, issued on an event.
+pub const EVENT_PULSE: u8 = 0x8A;
+/// The number of `NTSYNC_IOC_SEM_READ`, issued on an semaphore.
+pub const SEM_READ: u8 = 0x8B;
+/// The number of `NTSYNC_IOC_MUTEX_READ`, issued on an mutex.
+pub const MUTEX_READ: u8 = 0x8C;
+/// The number of `NTSYNC_IOC_EVENT_READ`, issued on an event.
+pub const EVENT_READ: u8 = 0x8D;
+
+/// The direction bit of an ioctl that passes data to the kernel.
+pub const WRITE: u64 = 1;
+/// The direction bit of an ioctl that returns data from the kernel.
+pub const READ: u64 = 2;
+
+/// Computes the request number like the `_IOC` macro of the kernel for the generic ioctl layout.
+pub const fn request_code(direction: u64, magic: u8, number: u8, size: usize) -> u64 {
+    (direction << 30) | ((size as u64) << 16) | ((magic as u64) << 8) | number as u64
+}
+
+#[cfg(semaphore)]
+#[cfg_attr(docsrs, doc(cfg(feature = "semaphore")))]
+/// The request number of `NTSYNC_IOC_CREATE_SEM`.
+pub const CREATE_SEM_REQUEST: u64 = request_code(WRITE, MAGIC, CREATE_SEM, size_of::<SemaphoreArgs>());
+/// The request number of `NTSYNC_IOC_SEM_RELEASE`.
+pub const SEM_RELEASE_REQUEST: u64 = request_code(READ | WRITE, MAGIC, SEM_RELEASE, size_of::<u32>());
+/// The request number of `NTSYNC_IOC_WAIT_ANY`.
+pub const WAIT_ANY_REQUEST: u64 = request_code(READ | WRITE, MAGIC, WAIT_ANY, size_of::<WaitArgs>());
+/// The request number of `NTSYNC_IOC_WAIT_ALL`.
+pub const WAIT_ALL_REQUEST: u64 = request_code(READ | WRITE, MAGIC, WAIT_ALL, size_of::<WaitArgs>());
+#[cfg(mutex)]
+#[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
+/// The request number of `NTSYNC_IOC_CREATE_MUTEX`.
+pub const CREATE_MUTEX_REQUEST: u64 = request_code(WRITE, MAGIC, CREATE_MUTEX, size_of::<MutexArgs>());
+#[cfg(mutex)]
+#[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
+/// The request number of `NTSYNC_IOC_MUTEX_UNLOCK`.
+pub const MUTEX_UNLOCK_REQUEST: u64 = request_code(READ | WRITE, MAGIC, MUTEX_UNLOCK, size_of::<MutexArgs>());
+/// The request number of `NTSYNC_IOC_MUTEX_KILL`.
+pub const MUTEX_KILL_REQUEST: u64 = request_code(WRITE, MAGIC, MUTEX_KILL, size_of::<u32>());
+/// The request number of `NTSYNC_IOC_CREATE_EVENT`.
+pub const CREATE_EVENT_REQUEST: u64 = request_code(WRITE, MAGIC, CREATE_EVENT, size_of::<EventArgs>());
+/// The request number of `NTSYNC_IOC_EVENT_SET`.
+pub const EVENT_SET_REQUEST: u64 = request_code(READ, MAGIC, EVENT_SET, size_of::<u32>());
+/// The request number of `NTSYNC_IOC_EVENT_RESET`.
+pub const EVENT_RESET_REQUEST: u64 = request_code(READ, MAGIC, EVENT_RESET, size_of::<u32>());
+/// The request number of `NTSYNC_IOC_EVENT_PULSE`.
+pub const EVENT_PULSE_REQUEST: u64 = request_code(READ, MAGIC, EVENT_PULSE, size_of::<u32>());
+#[cfg(semaphore)]
+#[cfg_attr(docsrs, doc(cfg(feature = "semaphore")))]
+/// The request number of `NTSYNC_IOC_SEM_READ`.
+pub const SEM_READ_REQUEST: u64 = request_code(READ, MAGIC, SEM_READ, size_of::<SemaphoreArgs>());
+#[cfg(mutex)]
+#[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
+/// The request number of `NTSYNC_IOC_MUTEX_READ`.
+pub const MUTEX_READ_REQUEST: u64 = request_code(READ, MAGIC, MUTEX_READ, size_of::<MutexArgs>());
+/// The request number of `NTSYNC_IOC_EVENT_READ`.
+pub const EVENT_READ_REQUEST: u64 = request_code(READ, MAGIC, EVENT_READ, size_of::<EventArgs>());
@@ -0,0 +1,93 @@
+//! Tracks the expected count of every semaphore to find releases without an matching acquisition.
+//!
+//! The table is keyed by the file descriptor and shared by all instances of the process.
+//! Acquisitions are recorded by [wait_all](crate::NtSync::wait_all) and [wait_any](crate::NtSync::wait_any), releases by [release](crate::Semaphore::release).
+//! Acquisitions by other processes or through file descriptors that were not created or adopted by this crate are not seen,
+//! so they show up as an divergence on the next [read](crate::NTSyncObjects::read).
+use std::{
+    collections::HashMap,
+    sync::{
+        LazyLock,
+        Mutex as StdMutex,
+        MutexGuard as StdMutexGuard,
+        PoisonError,
+    },
+};
+
+use log::*;
+
+use crate::{
+    EventSources,
+    Fd,
+    SemaphoreSnapshot,
+};
+
+#[derive(Debug)]
+struct Balance {
+    expected: u32,
+    max: u32,
+    warnings: u32,
+}
+
+static TABLE: LazyLock<StdMutex<HashMap<Fd, Balance>>> = LazyLock::new(StdMutex::default);
+
+fn table() -> StdMutexGuard<'static, HashMap<Fd, Balance>> {
+    TABLE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Starts tracking an semaphore with the count it has now.
+pub(crate) fn track(fd: Fd, status: &SemaphoreSnapshot) {
+    table().insert(
+        fd,
+        Balance {
+            expected: status.count,
+            max: status.max(),
+            warnings: 0,
+        },
+    );
+}
+
+/// Stops tracking an deleted semaphore, so the file descriptor can be reused.
+pub(crate) fn forget(fd: Fd) {
+    table().remove(&fd);
+}
+
+/// Records the acquisition of one permit of every semaphore in `sources`.
+pub(crate) fn acquired<'a>(sources: impl IntoIterator<Item = &'a EventSources>) {
+    let mut table = table();
+    for source in sources {
+        if let EventSources::Semaphore(semaphore) = source &&
+            let Some(balance) = table.get_mut(&semaphore.id)
+        {
+            balance.expected = balance.expected.saturating_sub(1);
+        }
+    }
+}
+
+/// Records an release and warns if more permits were released than acquired.
+pub(crate) fn released(fd: Fd, amount: u32) {
+    if let Some(balance) = table().get_mut(&fd) {
+        let outstanding = balance.max.saturating_sub(balance.expected);
+        if amount > outstanding {
+            balance.warnings += 1;
+            warn!(target: "ntsync", handle=fd; "Released {amount} permits, but only {outstanding} were acquired. This is an leak or an double release");
+        }
+        balance.expected = balance.expected.saturating_add(amount).min(balance.max);
+    }
+}
+
+/// Compares the count the kernel reported with the expected one and warns if they diverge.
+pub(crate) fn compare(fd: Fd, status: &SemaphoreSnapshot) {
+    if let Some(balance) = table().get_mut(&fd) &&
+        balance.expected != status.count
+    {
+        balance.warnings += 1;
+        warn!(target: "ntsync", handle=fd; "The semaphore has an count of {}, but {} was expected. Permits were acquired or released without being tracked", status.count, balance.expected);
+        balance.expected = status.count;
+    }
+}
+
+/// Returns the number of warnings that were logged for the semaphore.
+pub(crate) fn warnings(fd: Fd) -> u32 {
+    table().get(&fd).map_or(0, |balance| balance.warnings)
+}
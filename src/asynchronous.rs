@@ -0,0 +1,236 @@
+//! Bridges ntsync events to file descriptors that an async reactor can poll.
+//!
+//! The file descriptors of ntsync objects do not report readiness to `epoll`, so they can not be registered with tokio or mio directly.
+//! Instead an helper thread blocks in the kernel wait and writes to an `eventfd` once the event fires.
+use std::{
+    collections::HashSet,
+    future::Future,
+    io,
+    os::fd::{
+        AsFd,
+        AsRawFd,
+        BorrowedFd,
+        FromRawFd as _,
+        OwnedFd,
+    },
+    pin::Pin,
+    sync::{
+        Arc,
+        Mutex as StdMutex,
+        PoisonError,
+    },
+    task::{
+        Context,
+        Poll,
+        Waker,
+    },
+    thread::{
+        Builder,
+        JoinHandle,
+    },
+};
+
+use crate::errno::Errno;
+use log::*;
+
+use crate::{
+    Error,
+    Event,
+    EventSources,
+    Fd,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    OwnerId,
+    Result,
+    WaitAnyStatus,
+    cold_path,
+};
+
+#[derive(Debug)]
+/// An `eventfd` that becomes readable when the ntsync event it was created for fires.
+///
+/// It is created with [readiness_eventfd](Event::readiness_eventfd) and can be registered with an reactor, for example with `tokio::io::unix::AsyncFd`.
+/// Dropping it stops the helper thread and waits until it exited.
+pub struct ReadinessFd {
+    fd: OwnedFd,
+    stop: Event,
+    helper: Option<JoinHandle<()>>,
+}
+
+impl AsFd for ReadinessFd {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+impl AsRawFd for ReadinessFd {
+    fn as_raw_fd(&self) -> Fd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl Drop for ReadinessFd {
+    fn drop(&mut self) {
+        if let Err(error) = self.stop.signal() {
+            cold_path();
+            warn!(target: "ntsync", handle=self.stop.id; "Failed to stop the helper thread of an eventfd: {error}");
+            return;
+        }
+        if let Some(helper) = self.helper.take() &&
+            helper.join().is_err()
+        {
+            cold_path();
+            warn!(target: "ntsync", "The helper thread of an eventfd panicked");
+        }
+        if let Err(error) = self.stop.delete() {
+            cold_path();
+            warn!(target: "ntsync", handle=self.stop.id; "Failed to delete the stop event of an eventfd: {error}");
+        }
+    }
+}
+
+impl Event {
+    /// Returns an `eventfd` that becomes readable once this event fires.
+    ///
+    /// Each call starts an helper thread that blocks in [wait_any](NtSync::wait_any) on this event, so it costs an thread and its stack until the event fires or the [ReadinessFd] is dropped.
+    /// The helper waits once: an auto reset event is consumed by the helper, an manual reset event is left signaled.
+    /// After the event fired, call this again to wait for the next signal.
+    pub fn readiness_eventfd(&self, instance: &NtSync) -> Result<ReadinessFd> {
+        let fd = match Errno::result(unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) }) {
+            Ok(fd) => unsafe { OwnedFd::from_raw_fd(fd) },
+            Err(errno) => {
+                cold_path();
+                trace!(target: "ntsync", returncode=errno as i32; "Failed to create the eventfd");
+                return Err(Error::IOError(io::Error::from_raw_os_error(errno as i32)));
+            },
+        };
+        let writer = fd.try_clone().map_err(Error::IOError)?;
+        let stop = instance.new_event(false, true)?;
+        let (instance, event) = (instance.clone(), *self);
+        let helper = Builder::new().name("ntsync eventfd".to_owned()).spawn(move || {
+            match instance.wait_any(HashSet::from([event.into()]), None, None, NtSyncFlags::empty(), Some(stop)) {
+                Ok(status) if status.alerted => {},
+                Ok(_) => {
+                    let value = 1u64;
+                    if let Err(errno) = Errno::result(unsafe { libc::write(writer.as_raw_fd(), (&raw const value).cast(), size_of::<u64>()) }) {
+                        cold_path();
+                        warn!(target: "ntsync", handle=writer.as_raw_fd(), returncode=errno as i32; "Failed to write to the eventfd");
+                    }
+                },
+                Err(error) => {
+                    cold_path();
+                    warn!(target: "ntsync", handle=event.id; "The wait of the eventfd helper failed: {error}");
+                },
+            }
+        });
+        match helper {
+            Ok(helper) => {
+                Ok(ReadinessFd {
+                    fd,
+                    stop,
+                    helper: Some(helper),
+                })
+            },
+            Err(error) => {
+                cold_path();
+                if let Err(error) = stop.delete() {
+                    warn!(target: "ntsync", handle=stop.id; "Failed to delete the stop event: {error}");
+                }
+                Err(Error::IOError(error))
+            },
+        }
+    }
+}
+
+/// The result of the helper thread and the waker of the task that polls the [WaitAnyFuture].
+#[derive(Debug, Default)]
+struct WaitState {
+    result: Option<Result<WaitAnyStatus>>,
+    waker: Option<Waker>,
+}
+
+#[derive(Debug)]
+#[must_use = "the wait is cancelled when the future is dropped"]
+/// An [wait_any](NtSync::wait_any) that runs in an helper thread, created with [wait_any_async](NtSync::wait_any_async).
+///
+/// The kernel wait has no timeout, the deadline is left to the timer of the executor, for example with `tokio::time::timeout`.
+/// Dropping the future signals an internal alert, so the helper thread leaves the kernel wait immediately, and waits until it exited.
+pub struct WaitAnyFuture {
+    state: Arc<StdMutex<WaitState>>,
+    stop: Event,
+    helper: Option<JoinHandle<()>>,
+}
+
+impl Future for WaitAnyFuture {
+    type Output = Result<WaitAnyStatus>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            },
+        }
+    }
+}
+
+impl Drop for WaitAnyFuture {
+    fn drop(&mut self) {
+        if let Err(error) = self.stop.signal() {
+            cold_path();
+            warn!(target: "ntsync", handle=self.stop.id; "Failed to cancel the helper thread of an async wait: {error}");
+            return;
+        }
+        if let Some(helper) = self.helper.take() &&
+            helper.join().is_err()
+        {
+            cold_path();
+            warn!(target: "ntsync", "The helper thread of an async wait panicked");
+        }
+        if let Err(error) = self.stop.delete() {
+            cold_path();
+            warn!(target: "ntsync", handle=self.stop.id; "Failed to delete the stop event of an async wait: {error}");
+        }
+    }
+}
+
+impl NtSync {
+    /// Starts an [wait_any](NtSync::wait_any) in an helper thread and returns an future that completes with its result.
+    ///
+    /// The kernel waits without an timeout, so the timeout is set by wrapping the future in the timer of the executor.
+    /// When the timer fires, the future is dropped and the helper thread is stopped through an alert, so it does not outlive the future.
+    /// If the wait acquired objects right before the future was dropped, they stay acquired and are not returned to the caller.
+    /// Each wait costs an thread and its stack until it finished or was cancelled.
+    pub fn wait_any_async(&self, sources: HashSet<EventSources>, owner: Option<OwnerId>, flags: NtSyncFlags) -> Result<WaitAnyFuture> {
+        let stop = self.new_event(false, true)?;
+        let state = Arc::new(StdMutex::new(WaitState::default()));
+        let (instance, shared) = (self.clone(), Arc::clone(&state));
+        let helper = Builder::new().name("ntsync async wait".to_owned()).spawn(move || {
+            let result = instance.wait_any(sources, None, owner, flags, Some(stop));
+            let mut state = shared.lock().unwrap_or_else(PoisonError::into_inner);
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        match helper {
+            Ok(helper) => {
+                Ok(WaitAnyFuture {
+                    state,
+                    stop,
+                    helper: Some(helper),
+                })
+            },
+            Err(error) => {
+                cold_path();
+                if let Err(error) = stop.delete() {
+                    warn!(target: "ntsync", handle=stop.id; "Failed to delete the stop event: {error}");
+                }
+                Err(Error::IOError(error))
+            },
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use std::{
+    iter,
+    time::Duration,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// An exponential backoff for the helpers that have to poll the state of an object.
+///
+/// The delays start at `initial` and are multiplied by `factor` after every poll that saw no change, until they reach `max`.
+/// An short delay notices changes sooner but costs more CPU time, so an busy object is polled tightly and an idle one rarely.
+/// The helpers restart at `initial` when they see an change.
+pub struct Backoff {
+    /// The first delay.
+    pub initial: Duration,
+    /// The upper limit of the delay.
+    pub max: Duration,
+    /// The factor the delay is multiplied with after each poll without an change. With 1 the delay stays fixed.
+    pub factor: u32,
+}
+
+impl Backoff {
+    /// Creates an backoff that always waits for `interval`.
+    pub const fn fixed(interval: Duration) -> Self {
+        Backoff {
+            initial: interval,
+            max: interval,
+            factor: 1,
+        }
+    }
+
+    /// Returns the endless sequence of delays, starting at `initial` and capped at `max`.
+    pub fn delays(&self) -> impl Iterator<Item = Duration> + use<> {
+        let (factor, max) = (self.factor, self.max);
+        iter::successors(Some(self.initial.min(max)), move |delay| Some(delay.saturating_mul(factor).min(max)))
+    }
+}
+
+impl From<Duration> for Backoff {
+    fn from(interval: Duration) -> Self {
+        Backoff::fixed(interval)
+    }
+}
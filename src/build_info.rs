@@ -0,0 +1,51 @@
+/// The features this build of the crate was compiled with, returned by [build_info].
+///
+/// It is meant for bug reports and for code that has to work with different builds of the crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// The version of the crate.
+    pub version: &'static str,
+    /// The operating system the crate was compiled for. Outside of linux the features are enabled, but have no effect.
+    pub target_os: &'static str,
+    /// The names of the enabled cargo features, sorted by name.
+    pub features: Vec<&'static str>,
+}
+
+impl BuildInfo {
+    /// Returns true if the cargo feature `name` is enabled.
+    pub fn has_feature(&self, name: &str) -> bool {
+        self.features.contains(&name)
+    }
+
+    /// Returns the names of all cargo features of the crate, enabled or not, sorted by name.
+    pub fn known_features() -> Vec<&'static str> {
+        FEATURES.iter().map(|(name, _)| *name).collect()
+    }
+}
+
+/// Every cargo feature with the information if it is enabled, sorted by name.
+const FEATURES: [(&str, bool); 14] = [
+    ("async", cfg!(feature = "async")),
+    ("debug-event", cfg!(feature = "debug-event")),
+    ("debug-semaphore", cfg!(feature = "debug-semaphore")),
+    ("metrics", cfg!(feature = "metrics")),
+    ("minimal", cfg!(feature = "minimal")),
+    ("mutex", cfg!(feature = "mutex")),
+    ("nix", cfg!(feature = "nix")),
+    ("no-cold-hints", cfg!(feature = "no-cold-hints")),
+    ("random", cfg!(feature = "random")),
+    ("record", cfg!(feature = "record")),
+    ("semaphore", cfg!(feature = "semaphore")),
+    ("serde", cfg!(feature = "serde")),
+    ("unstable", cfg!(feature = "unstable")),
+    ("unstable_mutex", cfg!(feature = "unstable_mutex")),
+];
+
+/// Returns the version, target and features of this build, which are fixed at compile time.
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        target_os: std::env::consts::OS,
+        features: FEATURES.into_iter().filter(|(_, enabled)| *enabled).map(|(name, _)| name).collect(),
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::{
+        BTreeSet,
+        HashSet,
+    },
+    mem,
+    sync::{
+        Mutex as StdMutex,
+        MutexGuard as StdMutexGuard,
+        PoisonError,
+    },
+};
+
+use crate::{
+    MutexGuard,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    Result,
+    Semaphore,
+    cold_path,
+    semaphore::SemaphoreArgs,
+};
+
+#[derive(Debug)]
+/// An condition variable for the [Mutexes](crate::Mutex) of ntsync, like [std::sync::Condvar].
+///
+/// The kernel can not unlock an mutex and wait in one operation, so [wait](Condvar::wait) unlocks the mutex first and waits afterwards.
+/// To not lose an notification in between, the notifications are counted with an semaphore:
+/// an waiter is registered before it unlocks the mutex and every notification for an registered waiter releases an permit that stays until it is consumed.
+/// The waiter then waits with [wait_all](NtSync::wait_all) for an permit and the mutex together, so it only returns with the mutex locked again.
+/// Every waiter gets an ticket, so an waiter that fails before it was notified only unregisters itself and not an waiter that registered after it.
+///
+/// Like with [std::sync::Condvar] spurious wakeups are possible, so the condition has to be checked in an loop.
+pub struct Condvar {
+    instance: NtSync,
+    semaphore: Semaphore,
+    waiters: StdMutex<Tickets>,
+}
+
+#[derive(Debug, Default)]
+/// The tickets of the waiters in the order they registered. The notifications go to the tickets in that order.
+struct Tickets {
+    /// The ticket of the next waiter.
+    next: u64,
+    /// The tickets below it were notified.
+    notified: u64,
+    /// The waiters that failed before they were notified, the notifications skip them.
+    cancelled: BTreeSet<u64>,
+}
+
+impl Tickets {
+    fn register(&mut self) -> u64 {
+        self.next += 1;
+        self.next - 1
+    }
+
+    /// Unregisters an waiter that failed. An notified waiter keeps its permit, which only causes an spurious wakeup.
+    fn cancel(&mut self, ticket: u64) {
+        if ticket >= self.notified {
+            self.cancelled.insert(ticket);
+        }
+    }
+
+    /// Skips the cancelled tickets that are next in line and returns how many waiters were not notified yet.
+    fn pending(&mut self) -> u32 {
+        while self.cancelled.remove(&self.notified) {
+            self.notified += 1;
+        }
+        u32::try_from(self.next - self.notified - self.cancelled.len() as u64).unwrap_or(u32::MAX)
+    }
+}
+
+impl Condvar {
+    fn waiters(&self) -> StdMutexGuard<'_, Tickets> {
+        self.waiters.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Unlocks the mutex of the guard, waits for an notification and locks the mutex again for the same owner.
+    ///
+    /// The guard has to hold the only lock of its owner on the mutex, a recursively locked mutex stays locked and the wait can never succeed.
+    /// If an error is returned the mutex is not locked anymore.
+    pub fn wait(&self, guard: MutexGuard) -> Result<MutexGuard> {
+        let (mutex, owner) = (guard.mutex, guard.owner);
+        let ticket = self.waiters().register();
+        // The mutex is unlocked here and locked again by the wait, so the guard must not unlock it again.
+        mem::forget(guard);
+        let result = mutex.unlock(owner).and_then(|()| {
+            self.instance.wait_all(
+                HashSet::from([
+                    self.semaphore.into(),
+                    mutex.into(),
+                ]),
+                None,
+                Some(owner),
+                NtSyncFlags::empty(),
+                None,
+            )
+        });
+        if let Err(error) = result {
+            cold_path();
+            self.waiters().cancel(ticket);
+            return Err(error);
+        }
+        Ok(MutexGuard {
+            mutex,
+            owner,
+        })
+    }
+
+    /// Wakes up one waiting thread, if there is one.
+    pub fn notify_one(&self) -> Result<()> {
+        let mut waiters = self.waiters();
+        if waiters.pending() > 0 {
+            self.semaphore.release(1)?;
+            waiters.notified += 1;
+        }
+        Ok(())
+    }
+
+    /// Wakes up all waiting threads.
+    pub fn notify_all(&self) -> Result<()> {
+        let mut waiters = self.waiters();
+        let pending = waiters.pending();
+        if pending > 0 {
+            self.semaphore.release(pending)?;
+            waiters.notified = waiters.next;
+            waiters.cancelled.clear();
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Condvar {
+    fn drop(&mut self) {
+        if let Err(error) = self.semaphore.delete() {
+            self.instance.drop_failed(self.semaphore.id, "semaphore of an Condvar", &error);
+        }
+    }
+}
+
+impl NtSync {
+    /// Creates an [Condvar] without waiters.
+    pub fn new_condvar(&self) -> Result<Condvar> {
+        Ok(Condvar {
+            instance: self.clone(),
+            semaphore: self.create_semaphore(SemaphoreArgs::with_count(0, u32::MAX))?,
+            waiters: StdMutex::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_after_notification() {
+        let mut tickets = Tickets::default();
+        let (first, _second) = (tickets.register(), tickets.register());
+        assert_eq!(tickets.pending(), 2);
+        tickets.notified += 1;
+        tickets.cancel(first);
+        assert_eq!(tickets.pending(), 1, "The notified waiter took the registration of the second one");
+    }
+
+    #[test]
+    fn cancel_before_notification() {
+        let mut tickets = Tickets::default();
+        let (first, second, _third) = (tickets.register(), tickets.register(), tickets.register());
+        tickets.cancel(second);
+        tickets.cancel(first);
+        assert_eq!(tickets.pending(), 1);
+        assert_eq!(tickets.notified, 2, "The cancelled tickets were not skipped");
+        assert!(tickets.cancelled.is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    process,
+    sync::atomic::Ordering,
+};
+
+use log::*;
+
+use crate::{
+    Error,
+    Fd,
+    NtSync,
+    cold_path,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(u8)]
+/// Decides what happens when an wrapper fails to delete its objects while it is dropped, set with [set_drop_policy](NtSync::set_drop_policy).
+///
+/// It applies to the wrappers that keep an instance: [Parker](crate::Parker), [SharedEvent](crate::SharedEvent), [OneShot](crate::OneShot),
+/// [LatencyProbe](crate::LatencyProbe), [ReusableAlert](crate::ReusableAlert),
+#[cfg_attr(semaphore, doc = "[FairSemaphore](crate::FairSemaphore),")]
+#[cfg_attr(all(mutex, semaphore), doc = "[Condvar](crate::Condvar),")]
+/// but not to the guards, which only hold the object.
+pub enum DropPolicy {
+    /// The failure is ignored.
+    Silent,
+    /// The failure is logged as warning.
+    #[default]
+    LogErrors,
+    /// The failure is logged as error and the process is aborted, because the crate does not panic.
+    Abort,
+}
+
+impl DropPolicy {
+    pub(crate) fn from_u8(value: u8) -> Self {
+        match value {
+            0 => DropPolicy::Silent,
+            2 => DropPolicy::Abort,
+            _ => DropPolicy::LogErrors,
+        }
+    }
+}
+
+impl NtSync {
+    /// Sets the [DropPolicy] of this instance and all of its clones.
+    pub fn set_drop_policy(&self, policy: DropPolicy) {
+        self.inner.drop_policy.store(policy as u8, Ordering::Relaxed);
+    }
+
+    /// Returns the [DropPolicy] of this instance.
+    pub fn drop_policy(&self) -> DropPolicy {
+        DropPolicy::from_u8(self.inner.drop_policy.load(Ordering::Relaxed))
+    }
+
+    /// Returns how many failures at drop were logged, so [DropPolicy::Silent] failures are not counted.
+    pub fn logged_drop_failures(&self) -> u64 {
+        self.inner.drop_failures.load(Ordering::Relaxed)
+    }
+
+    /// Handles an failed deletion of `what` while it was dropped, according to the [DropPolicy].
+    pub(crate) fn drop_failed(&self, handle: Fd, what: &str, error: &Error) {
+        cold_path();
+        match self.drop_policy() {
+            DropPolicy::Silent => {},
+            DropPolicy::LogErrors => {
+                self.inner.drop_failures.fetch_add(1, Ordering::Relaxed);
+                warn!(target: "ntsync", handle=handle; "Failed to delete the {what}: {error}");
+            },
+            DropPolicy::Abort => {
+                self.inner.drop_failures.fetch_add(1, Ordering::Relaxed);
+                error!(target: "ntsync", handle=handle; "Failed to delete the {what}, aborting: {error}");
+                process::abort();
+            },
+        }
+    }
+}
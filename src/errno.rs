@@ -0,0 +1,95 @@
+//! The errno type the wrappers match on.
+//!
+//! With the `nix` feature it is [Errno](nix::errno::Errno) of nix. Without it an small replacement with the errnos the crate handles is used,
+//! which is read with [std::io::Error::last_os_error]. Other errnos are reported as `Unknown`, which is 0 like `UnknownErrno` of nix.
+
+use std::result;
+
+#[cfg(use_nix)]
+pub(crate) use nix::errno::Errno;
+
+/// The result of an libc call that sets the errno on failure.
+pub(crate) type Result<T> = result::Result<T, Errno>;
+
+#[cfg(not(use_nix))]
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+/// The errnos the crate handles, with the values of libc.
+pub(crate) enum Errno {
+    Unknown    = 0,
+    EPERM      = libc::EPERM,
+    EINTR      = libc::EINTR,
+    EIO        = libc::EIO,
+    EBADF      = libc::EBADF,
+    ENOTTY     = libc::ENOTTY,
+    ENFILE     = libc::ENFILE,
+    EMFILE     = libc::EMFILE,
+    ENOSPC     = libc::ENOSPC,
+    EINVAL     = libc::EINVAL,
+    EOVERFLOW  = libc::EOVERFLOW,
+    ETIMEDOUT  = libc::ETIMEDOUT,
+    EDQUOT     = libc::EDQUOT,
+    EOWNERDEAD = libc::EOWNERDEAD,
+}
+
+#[cfg(not(use_nix))]
+/// The return types of libc calls that return -1 on failure.
+pub(crate) trait Sentinel: PartialEq + Copy {
+    /// The value that signals an failure.
+    const FAILED: Self;
+}
+
+#[cfg(not(use_nix))]
+impl Sentinel for libc::c_int {
+    const FAILED: Self = -1;
+}
+
+#[cfg(not(use_nix))]
+impl Sentinel for libc::ssize_t {
+    const FAILED: Self = -1;
+}
+
+#[cfg(not(use_nix))]
+impl Errno {
+    /// Returns the errno of the last failed call of this thread.
+    pub(crate) fn last() -> Self {
+        Self::from_raw(std::io::Error::last_os_error().raw_os_error().unwrap_or_default())
+    }
+
+    /// Converts an raw errno, errnos that the crate does not handle become `Unknown`.
+    pub(crate) fn from_raw(errno: i32) -> Self {
+        match errno {
+            libc::EPERM => Self::EPERM,
+            libc::EINTR => Self::EINTR,
+            libc::EIO => Self::EIO,
+            libc::EBADF => Self::EBADF,
+            libc::ENOTTY => Self::ENOTTY,
+            libc::ENFILE => Self::ENFILE,
+            libc::EMFILE => Self::EMFILE,
+            libc::ENOSPC => Self::ENOSPC,
+            libc::EINVAL => Self::EINVAL,
+            libc::EOVERFLOW => Self::EOVERFLOW,
+            libc::ETIMEDOUT => Self::ETIMEDOUT,
+            libc::EDQUOT => Self::EDQUOT,
+            libc::EOWNERDEAD => Self::EOWNERDEAD,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Returns the errno of the thread if `value` signals an failure, otherwise the value.
+    pub(crate) fn result<S: Sentinel>(value: S) -> Result<S> {
+        if value == S::FAILED {
+            Err(Self::last())
+        } else {
+            Ok(value)
+        }
+    }
+}
+
+#[cfg(not(use_nix))]
+impl std::fmt::Display for Errno {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{self:?}: {}", std::io::Error::from_raw_os_error(*self as i32)))
+    }
+}
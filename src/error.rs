@@ -3,6 +3,9 @@ use std::{
     io::Error as IOError,
 };
 
+#[cfg(mutex)]
+use crate::OwnerId;
+
 #[derive(Debug)]
 /// An Enum that is used to return different Errors from the Kernel.
 /// Since there is not much Information returned its mostly an simple enum.
@@ -27,13 +30,121 @@ pub enum Error {
     DuplicateEvent,
     /// Returned when an object is closed at least twice
     AlreadyClosed,
+    /// Returned when [wait_all](crate::NtSync::wait_all) or [wait_any](crate::NtSync::wait_any) are called without any object to wait on.
+    EmptyWaitSet,
+    /// Returned when objects of different [NtSync](crate::NtSync) instances are combined, which the kernel does not support.
+    CrossInstance,
+    /// Returned when an adopted file descriptor is not an object of the `expected` type, for example an event that is adopted as semaphore.
+    WrongObjectType {
+        /// The type of object that was expected.
+        expected: &'static str,
+    },
+    /// Returned when an owner would lock an mutex deeper than [max_depth](crate::Mutex::max_depth).
+    DepthExceeded,
+    /// Returned by [lock_non_recursive](crate::Mutex::lock_non_recursive) when the owner already holds the mutex.
+    WouldRecurse,
+    /// Returned by [unlock](crate::Mutex::unlock) when the mutex is not held by the owner that tried to unlock it.
+    #[cfg(mutex)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
+    WrongOwner {
+        /// The owner that tried to unlock the mutex.
+        expected: OwnerId,
+        /// The owner the mutex had after the failed unlock, [None] if it was not locked.
+        actual: Option<OwnerId>,
+    },
+    /// Returned by the waits when the file descriptor of the device was closed, while the objects can still be open.
+    DeviceGone,
+    /// Returned by the waits when the alert was closed before the wait, so the kernel rejected it.
+    AlertInvalid,
+    /// Returned when an object can not be created because of an limit of open files or kernel objects, for example of an cgroup or an container.
+    QuotaExceeded,
+    /// Returned when an instance from [with_object_limit](crate::NtSync::with_object_limit) has as many objects alive as its limit allows.
+    ResourceExhausted,
+    /// Returned by [new_independent](crate::NtSync::new_independent) when the instance was created from an file descriptor, so there is no path to open the device again.
+    NoDevicePath,
+    /// Returned by [wait_all_nonblocking_semaphores](crate::NtSync::wait_all_nonblocking_semaphores) when an semaphore of the set has no permit left.
+    WouldBlock,
     /// When an unknown errno is set this is returned, so that an panic is prevented.
     Unknown(i32),
+    /// An error together with the name of the operation that returned it, see [with_context](Error::with_context).
+    ///
+    /// The waits and the operations on the objects, like [signal](crate::Event::signal) or `release`, attach their name.
+    /// It compares equal to the bare error, so `assert_eq!` and `==` ignore the context. Patterns have to match on [kind](Error::kind) instead.
+    Context {
+        /// The name of the operation, for example `"wait_all"`.
+        operation: &'static str,
+        /// The error the operation returned.
+        error: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Attaches the name of the operation that returned the error.
+    ///
+    /// An error that already carries an context keeps it, so the innermost operation that failed is reported.
+    pub fn with_context(self, operation: &'static str) -> Self {
+        match self {
+            Self::Context {
+                ..
+            } => self,
+            error => {
+                Self::Context {
+                    operation,
+                    error: Box::new(error),
+                }
+            },
+        }
+    }
+
+    /// Returns the name of the operation that returned the error, if it was attached.
+    pub fn operation(&self) -> Option<&'static str> {
+        match self {
+            Self::Context {
+                operation,
+                ..
+            } => Some(operation),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without the context, for matching on the variant.
+    pub fn kind(&self) -> &Error {
+        match self {
+            Self::Context {
+                error,
+                ..
+            } => error.kind(),
+            error => error,
+        }
+    }
+
+    /// Works like [kind](Error::kind), but takes the error.
+    pub fn into_kind(self) -> Error {
+        match self {
+            Self::Context {
+                error,
+                ..
+            } => error.into_kind(),
+            error => error,
+        }
+    }
 }
 
 impl PartialEq for Error {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
+            (
+                Self::Context {
+                    ..
+                },
+                _,
+            ) |
+            (
+                _,
+                Self::Context {
+                    ..
+                },
+            ) => self.kind() == other.kind(),
             (Self::NotExist, Self::NotExist) => true,
             (Self::InvalidValue, Self::InvalidValue) => true,
             (Self::SemaphoreOverflow, Self::SemaphoreOverflow) => true,
@@ -42,6 +153,35 @@ impl PartialEq for Error {
             (Self::OwnerDead, Self::OwnerDead) => true,
             (Self::Interrupt, Self::Interrupt) => true,
             (Self::AlreadyClosed, Self::AlreadyClosed) => true,
+            (Self::EmptyWaitSet, Self::EmptyWaitSet) => true,
+            (Self::CrossInstance, Self::CrossInstance) => true,
+            (Self::DepthExceeded, Self::DepthExceeded) => true,
+            (Self::WouldRecurse, Self::WouldRecurse) => true,
+            (Self::DeviceGone, Self::DeviceGone) => true,
+            (Self::AlertInvalid, Self::AlertInvalid) => true,
+            (Self::QuotaExceeded, Self::QuotaExceeded) => true,
+            (Self::ResourceExhausted, Self::ResourceExhausted) => true,
+            (Self::NoDevicePath, Self::NoDevicePath) => true,
+            (Self::WouldBlock, Self::WouldBlock) => true,
+            #[cfg(mutex)]
+            (
+                Self::WrongOwner {
+                    expected: expected_a,
+                    actual: actual_a,
+                },
+                Self::WrongOwner {
+                    expected: expected_b,
+                    actual: actual_b,
+                },
+            ) => expected_a == expected_b && actual_a == actual_b,
+            (
+                Self::WrongObjectType {
+                    expected: a,
+                },
+                Self::WrongObjectType {
+                    expected: b,
+                },
+            ) => a == b,
             (Self::Unknown(a), Self::Unknown(b)) => a == b,
             (..) => false,
         }
@@ -61,7 +201,34 @@ impl Display for Error {
             Self::Interrupt => f.write_str("Interrupt received"),
             Self::DuplicateEvent => f.write_str("An Event is part of the sources and was added as an Alert"),
             Self::AlreadyClosed => f.write_str("Tried to use an already closed object"),
+            Self::EmptyWaitSet => f.write_str("There are no objects to wait on"),
+            Self::CrossInstance => f.write_str("Objects of different instances can not be used together"),
+            Self::WrongObjectType {
+                expected,
+            } => f.write_fmt(format_args!("The file descriptor is not an {expected}")),
+            Self::DepthExceeded => f.write_str("The mutex is already locked at the maximum depth"),
+            Self::WouldRecurse => f.write_str("The owner already holds the mutex"),
+            Self::DeviceGone => f.write_str("The ntsync device was closed"),
+            Self::AlertInvalid => f.write_str("The alert of the wait is not an open event"),
+            Self::QuotaExceeded => f.write_str("An limit of open files or kernel objects prevents the creation"),
+            Self::ResourceExhausted => f.write_str("The instance reached its limit of objects"),
+            Self::NoDevicePath => f.write_str("The instance was created from an file descriptor and has no path to open the device again"),
+            Self::WouldBlock => f.write_str("An semaphore of the wait has no permit left"),
+            #[cfg(mutex)]
+            Self::WrongOwner {
+                expected,
+                actual: Some(actual),
+            } => f.write_fmt(format_args!("The mutex is owned by {actual}, not by {expected}")),
+            #[cfg(mutex)]
+            Self::WrongOwner {
+                expected,
+                actual: None,
+            } => f.write_fmt(format_args!("The mutex is not locked, so {expected} can not unlock it")),
             Self::Unknown(errno) => f.write_fmt(format_args!("Unknown errno received: {errno}")),
+            Self::Context {
+                operation,
+                error,
+            } => f.write_fmt(format_args!("{operation}: {error}")),
         }
     }
 }
@@ -1,155 +1,558 @@
 use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    hash::{
+        Hash,
+        Hasher,
+    },
     io::Error as IOError,
-    os::fd::AsRawFd as _,
+    os::fd::{
+        AsRawFd,
+        FromRawFd,
+        OwnedFd,
+    },
+    thread,
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+    },
+};
+#[cfg(debug_event)]
+use std::{
+    collections::HashMap,
+    sync::{
+        LazyLock,
+        Mutex as StdMutex,
+        MutexGuard as StdMutexGuard,
+        PoisonError,
+    },
 };
 
+use crate::errno::Errno;
 use derive_new::new;
-use nix::{
-    errno::Errno,
-    ioctl_read,
-    ioctl_write_ptr,
-    libc,
-};
 
 use crate::{
     Error,
     EventSources,
     Fd,
-    NTSYNC_MAGIC,
     NTSyncObjects,
     NtSync,
+    NtSyncFlags,
     Result,
     Sealed,
+    abi,
+    adopt,
     cold_path,
+    create_error,
+    ioctl::{
+        ioctl_read,
+        ioctl_write_ptr,
+    },
     raw,
 };
 use log::*;
 
+/// The number of lost signals [signal_tracked](Event::signal_tracked) warned about, per file descriptor.
+#[cfg(debug_event)]
+static LOST_SIGNALS: LazyLock<StdMutex<HashMap<Fd, u32>>> = LazyLock::new(StdMutex::default);
+
+#[cfg(debug_event)]
+fn lost_signals() -> StdMutexGuard<'static, HashMap<Fd, u32>> {
+    LOST_SIGNALS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 
 #[repr(C)]
 #[derive(Debug, new, Default)]
 #[new(visibility = "pub(crate)")]
-/// Represents the Status of the Event at the moment of the Query.
-pub struct EventStatus {
+/// The arguments of the event ioctls, `struct ntsync_event_args` of the kernel.
+pub(crate) struct EventArgs {
     manual: u32,
     signaled: u32,
 }
 
-impl EventStatus {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(serialize, derive(serde::Serialize))]
+/// Represents the Status of the Event at the moment of the Query.
+pub struct EventSnapshot {
+    manual_reset: bool,
+    signaled: bool,
+}
+
+impl EventSnapshot {
     /// Returns true if the event is an manual reset event
     pub fn manual_reset(&self) -> bool {
-        self.manual == 1
+        self.manual_reset
     }
 
     /// Returns true if the event was automatically triggered.
     pub fn signaled(&self) -> bool {
-        self.signaled == 1
+        self.signaled
+    }
+
+    /// Returns true if an wait on the event would pass without blocking at the moment of the query.
+    ///
+    /// For an automatic reset event this means that the next waiter is released immediately and consumes the signal,
+    /// so only one waiter passes and the signal can already be gone when the snapshot is looked at.
+    /// For an manual reset event it means that all waiters pass until the event is reset.
+    /// Use [manual_reset](EventSnapshot::manual_reset) to tell which case applies.
+    pub fn has_pending_signal(&self) -> bool {
+        self.signaled
+    }
+}
+
+impl From<EventArgs> for EventSnapshot {
+    fn from(args: EventArgs) -> Self {
+        EventSnapshot::from(&args)
     }
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+impl From<&EventArgs> for EventSnapshot {
+    fn from(args: &EventArgs) -> Self {
+        EventSnapshot {
+            manual_reset: args.manual != 0,
+            signaled: args.signaled != 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
 /// An Event is an Type that can send Signals to other parts of the code.
 /// They can be automatically or manually reset.
 /// With manually reset events all waiting threads are worken up, but with automatically reset Event only one wakes up and can do the work.
 /// <div class="warning">An Automatically reset Event can trigger multiple times in a row and wake a whole lot of threads up</div>
+///
+/// The ordering is by the file descriptor, so it is only meaningful inside the current process.
+/// The equality and the hash also only use the file descriptor, so copies that know the type and copies that do not are the same event.
 pub struct Event {
     pub(crate) id: Fd,
+    /// The type the event was created with, [None] if it was wrapped from an file descriptor and is read on demand.
+    pub(crate) manual: Option<bool>,
+}
+
+impl PartialEq for Event {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Event {}
+
+impl Hash for Event {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl PartialOrd for Event {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Event {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
 }
 
 impl Event {
+    /// Returns true if the event is an manual reset event, without asking the kernel if the type is known.
+    ///
+    /// The type can not change after the creation, so the events of [new_event](NtSync::new_event) and [EventBuilder] remember it.
+    /// An event wrapped with [from_raw_fd](FromRawFd::from_raw_fd) or [adopt_event](NtSync::adopt_event) does not know it,
+    /// so each call reads the status. [Event] is [Copy] and can not cache it, wrap such an event again with the type if it is known.
+    pub fn is_manual(&self) -> Result<bool> {
+        match self.manual {
+            Some(manual) => Ok(manual),
+            None => Ok(self.status()?.manual_reset()),
+        }
+    }
+
     /// Triggers the event and signals all Threads waiting on it.
     /// The Event has to be reset after callling the function
     /// It returns if the signal was previously triggered
     pub fn signal(&self) -> Result<bool> {
         let mut state: u32 = 0;
-        match unsafe { ntsync_event_set(self.id, raw!(mut state: u32)) } {
+        let result = unsafe { ntsync_event_set(self.id, raw!(mut state: u32)) };
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::SetEvent {
+                    object: recorder.object((*self).into()),
+                },
+                crate::record::Outcome::of(&result, || crate::record::Outcome::Value(state)),
+            )
+        });
+        let outcome = match result {
             Ok(_) => Ok(state != 0),
             Err(Errno::EINVAL) => Err(Error::InvalidValue),
             Err(Errno::EBADF) => Err(Error::AlreadyClosed),
             Err(errno) => {
                 cold_path();
-                trace!(target: "ntsync", handle=self.id, returncode=errno as i32 ;"Failed to signal event");
+                trace!(target: "ntsync", handle=self.id, kind="event", returncode=errno as i32 ;"Failed to signal event");
                 Err(Error::Unknown(errno as i32))
             },
+        };
+        outcome.map_err(|error| error.with_context("signal"))
+    }
+
+    #[cfg(debug_event)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "debug-event")))]
+    /// Works like [signal](Event::signal), but warns if an automatic reset event was already signaled.
+    ///
+    /// Signals of an automatic reset event are not queued, so the second of two signals before an wait consumed the first is lost.
+    /// This is only an heuristic: the signal can be intended, and an signal that is consumed right before this one is not detected.
+    /// The reset mode is read with an separate status read, so it costs an additional ioctl.
+    pub fn signal_tracked(&self) -> Result<bool> {
+        let manual = self.status()?.manual_reset();
+        let signaled = self.signal()?;
+        if signaled && !manual {
+            cold_path();
+            *lost_signals().entry(self.id).or_default() += 1;
+            warn!(target: "ntsync", handle=self.id, kind="event"; "Signaled an automatic reset event that was already signaled, the signal is lost");
         }
+        Ok(signaled)
+    }
+
+    #[cfg(debug_event)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "debug-event")))]
+    /// Returns how often [signal_tracked](Event::signal_tracked) warned about an lost signal of this event.
+    pub fn lost_signal_warnings(&self) -> u32 {
+        lost_signals().get(&self.id).copied().unwrap_or(0)
     }
 
     /// [Event::reset] resets manual Events. It does nothing in Automatic Events
     pub fn reset(&self) -> Result<bool> {
         let mut state: u32 = 0;
-        match unsafe { ntsync_event_reset(self.id, raw!(mut state: u32)) } {
+        let result = unsafe { ntsync_event_reset(self.id, raw!(mut state: u32)) };
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::ResetEvent {
+                    object: recorder.object((*self).into()),
+                },
+                crate::record::Outcome::of(&result, || crate::record::Outcome::Value(state)),
+            )
+        });
+        let outcome = match result {
             Ok(_) => Ok(state != 0),
             Err(Errno::EINVAL) => Err(Error::InvalidValue),
             Err(Errno::EBADF) => Err(Error::AlreadyClosed),
             Err(errno) => {
                 cold_path();
-                trace!(target: "ntsync", handle=self.id, returncode=errno as i32 ;"Failed to reset event");
+                trace!(target: "ntsync", handle=self.id, kind="event", returncode=errno as i32 ;"Failed to reset event");
                 Err(Error::Unknown(errno as i32))
             },
-        }
+        };
+        outcome.map_err(|error| error.with_context("reset"))
     }
 
     /// Sets and resets the Event in an Atomic Operation.
     /// Simultanous Reads will show the Event as unsignaled
     pub fn pulse(&self) -> Result<bool> {
         let mut state: u32 = 0;
-        match unsafe { ntsync_event_pulse(self.id, raw!(mut state: u32)) } {
+        let result = unsafe { ntsync_event_pulse(self.id, raw!(mut state: u32)) };
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::PulseEvent {
+                    object: recorder.object((*self).into()),
+                },
+                crate::record::Outcome::of(&result, || crate::record::Outcome::Value(state)),
+            )
+        });
+        let outcome = match result {
             Ok(_) => Ok(state != 0),
             Err(Errno::EINVAL) => Err(Error::InvalidValue),
             Err(Errno::EBADF) => Err(Error::AlreadyClosed),
             Err(errno) => {
                 cold_path();
-                trace!(target: "ntsync", handle=self.id, returncode=errno as i32 ;"Failed to pulse event");
+                trace!(target: "ntsync", handle=self.id, kind="event", returncode=errno as i32 ;"Failed to pulse event");
                 Err(Error::Unknown(errno as i32))
             },
-        }
+        };
+        outcome.map_err(|error| error.with_context("pulse"))
     }
 
     /// Returns the Status at the moment of the Query.
-    pub fn status(&self) -> Result<EventStatus> {
-        let mut args = EventStatus::default();
-        match unsafe { ntsync_event_read(self.id, raw!(mut args: EventStatus)) } {
-            Ok(_) => Ok(args),
+    pub fn status(&self) -> Result<EventSnapshot> {
+        self.status_into(&mut EventArgs::default())
+    }
+
+    /// Reads the status into `args`, so callers that read many events can reuse one buffer.
+    fn status_into(&self, args: &mut EventArgs) -> Result<EventSnapshot> {
+        let result = unsafe { ntsync_event_read(self.id, &raw mut *args) };
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::ReadEvent {
+                    object: recorder.object((*self).into()),
+                },
+                crate::record::Outcome::of(&result, || crate::record::Outcome::Status(args.manual, args.signaled)),
+            )
+        });
+        match result {
+            Ok(_) => Ok(EventSnapshot::from(&*args)),
             Err(Errno::EBADF) => {
                 cold_path();
-                trace!(target: "ntsync", handle=self.id ;"Event is already closed");
+                trace!(target: "ntsync", handle=self.id, kind="event" ;"Event is already closed");
                 Err(Error::AlreadyClosed)
             },
             Err(errno) => {
                 cold_path();
-                trace!(target: "ntsync", handle=self.id, returncode=errno as i32 ;"Failed to query event");
+                trace!(target: "ntsync", handle=self.id, kind="event", returncode=errno as i32 ;"Failed to query event");
                 Err(Error::Unknown(errno as i32))
             },
         }
     }
+
+    /// Waits on the event until `predicate` returns true and returns true, or false if the timeout passed before.
+    ///
+    /// The predicate is checked before the first wait and after every wakeup, so an signal whose condition is not met yet only restarts the wait.
+    /// The deadline is fixed at the start, so every wait only gets the remaining time. Like with [Condvar](std::sync::Condvar::wait_while) the condition
+    /// has to be changed before the event is signaled. At the deadline the predicate is checked one last time. Errors of the predicate and of the wait are returned directly.
+    /// An timeout that is too large for an [Instant] waits without an deadline.
+    pub fn wait_until(&self, instance: &NtSync, timeout: Option<Duration>, mut predicate: impl FnMut() -> Result<bool>) -> Result<bool> {
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        while !predicate()? {
+            match instance.wait_any_with(HashSet::from([(*self).into()]), deadline, None, NtSyncFlags::empty(), None) {
+                Ok(_) => trace!(target: "ntsync", handle=self.id, kind="event"; "Woke up, checking the predicate again"),
+                Err(error) if matches!(error.kind(), Error::Timeout) => return predicate(),
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(true)
+    }
+
+    /// Waits for `within` and takes the signal back if no consumer took it in that time. Returns true if the signal was taken back.
+    ///
+    /// This cancels an handoff through an automatic reset event after an deadline. The check and the clearing are one [reset](Event::reset),
+    /// so the signal is either consumed by an waiter or taken back, never both. An consumer that arrives right after the deadline finds the event unsignaled.
+    /// An manual reset event is reset as well, even if waiters already passed it.
+    pub fn reclaim_if_unconsumed(&self, within: Duration) -> Result<bool> {
+        thread::sleep(within);
+        let reclaimed = self.reset()?;
+        if reclaimed {
+            trace!(target: "ntsync", handle=self.id, kind="event"; "Took back an signal nobody consumed");
+        }
+        Ok(reclaimed)
+    }
+
+    /// Replaces the event with an new one of the requested reset mode, that has the same signaled state.
+    ///
+    /// The kernel fixes the reset mode at the creation, so the event is recreated and the returned event has an other file descriptor.
+    /// Copies of the old event and waits on it are not moved to the new one.
+    /// The state is read, the new event created and the old one deleted in separate steps, so an signal or wait in between is lost.
+    /// If the old event can not be deleted, the new one is deleted and the error is returned.
+    pub fn with_mode(self, instance: &NtSync, manual: bool) -> Result<Event> {
+        let signaled = self.status()?.signaled();
+        let event = instance.new_event(signaled, manual)?;
+        if let Err(error) = self.delete() {
+            cold_path();
+            if let Err(delete_error) = event.delete() {
+                warn!(target: "ntsync", handle=event.id, kind="event"; "Failed to delete the replacement event: {delete_error}");
+            }
+            return Err(error);
+        }
+        Ok(event)
+    }
 }
 
 unsafe impl Send for Event {}
 unsafe impl Sync for Event {}
 
+/// Waits on one event with the realtime clock and returns false instead of [Error::Timeout].
+pub(crate) fn wait_event_bool(instance: &NtSync, event: Event, timeout: Option<SystemTime>) -> Result<bool> {
+    match instance.wait_any(HashSet::from([event.into()]), timeout, None, NtSyncFlags::WaitRealtime, None) {
+        Ok(_) => Ok(true),
+        Err(error) if matches!(error.kind(), Error::Timeout) => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
 impl From<Event> for EventSources {
     fn from(val: Event) -> Self {
         EventSources::Event(val)
     }
 }
 
+impl AsRawFd for Event {
+    fn as_raw_fd(&self) -> Fd {
+        self.id
+    }
+}
+
+impl FromRawFd for Event {
+    /// Wraps an file descriptor that was received from another part of the program or another process.
+    ///
+    /// # Safety
+    /// The file descriptor must be an event of the ntsync device, otherwise all operations fail.
+    unsafe fn from_raw_fd(fd: Fd) -> Self {
+        Event {
+            id: fd,
+            manual: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// An Builder for [Events](Event), created with [NtSync::event_builder].
+///
+/// By default it creates an unsignaled automatic reset event.
+pub struct EventBuilder {
+    instance: NtSync,
+    signaled: bool,
+    manual: bool,
+    strict: bool,
+}
+
+impl EventBuilder {
+    /// Sets if the event is signaled after the creation.
+    pub fn signaled(mut self, signaled: bool) -> Self {
+        self.signaled = signaled;
+        self
+    }
+
+    /// Sets if the event has to be reset manually.
+    pub fn manual(mut self, manual: bool) -> Self {
+        self.manual = manual;
+        self
+    }
+
+    /// Enables the strict mode, that rejects configurations which are valid but most likely created by mistake.
+    ///
+    /// Currently it rejects signaled automatic reset events, because the first waiter consumes the signal immediately.
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Creates the event. In strict mode it returns [Error::InvalidValue] for the rejected configurations.
+    pub fn build(self) -> Result<Event> {
+        if self.strict && self.signaled && !self.manual {
+            cold_path();
+            warn!(target: "ntsync", kind="event"; "Rejected an signaled automatic reset event. The first waiter will consume the signal immediately");
+            return Err(Error::InvalidValue);
+        }
+        self.instance.new_event(self.signaled, self.manual)
+    }
+}
+
 impl NtSync {
+    /// Reads the status of every event, in the order of `events`.
+    ///
+    /// The kernel has no batched status read, so this is still one ioctl per event.
+    /// It only saves the setup per call, because all reads share one buffer and the result is allocated once.
+    /// Each read can fail on its own, an failed read does not stop the others.
+    pub fn poll_event_statuses(&self, events: &[Event]) -> Vec<Result<EventSnapshot>> {
+        let mut args = EventArgs::default();
+        events.iter().map(|event| event.status_into(&mut args)).collect()
+    }
+
+    /// Wraps an event that was received as file descriptor, for example with `SCM_RIGHTS` from another process.
+    ///
+    /// The file descriptor is probed with an status read. If it is not an event [Error::WrongObjectType] is returned and the file descriptor is closed.
+    /// The kernel can only wait on objects of the same device, so the event has to be created by an instance that shares the device with this one.
+    pub fn adopt_event(&self, fd: OwnedFd) -> Result<Event> {
+        let event: Event = adopt(fd, "event")?;
+        #[cfg(metrics)]
+        crate::metrics::created(self.inner.handle.as_raw_fd(), event.into());
+        Ok(event)
+    }
+
+    /// Returns an [EventBuilder] for an unsignaled automatic reset event.
+    pub fn event_builder(&self) -> EventBuilder {
+        EventBuilder {
+            instance: self.clone(),
+            signaled: false,
+            manual: false,
+            strict: false,
+        }
+    }
+
     /// Creates a new Event.
     /// if signaled is true the threads begin the work as soo they are waiting.
     /// when manual is true, the event has to be reset manually.
     /// if manual is false after the first thread successful waits on it, the signaled status is set to false.
     pub fn new_event(&self, signaled: bool, manual: bool) -> Result<Event> {
-        let args = EventStatus::new(signaled as u32, manual as u32);
-        match unsafe { ntsync_create_event(self.inner.handle.as_raw_fd(), raw!(const args: EventStatus)) } {
+        let args = EventArgs::new(manual as u32, signaled as u32);
+        let result = self.create_limited(|| unsafe { ntsync_create_event(self.inner.handle.as_raw_fd(), raw!(const args: EventArgs)) })?;
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::CreateEvent {
+                    signaled,
+                    manual,
+                },
+                recorder.created(&result, |id| {
+                    EventSources::Event(Event {
+                        id,
+                        manual: Some(manual),
+                    })
+                }),
+            )
+        });
+        match result {
             Ok(fd) => {
+                #[cfg(metrics)]
+                crate::metrics::created(
+                    self.inner.handle.as_raw_fd(),
+                    EventSources::Event(Event {
+                        id: fd,
+                        manual: Some(manual),
+                    }),
+                );
                 Ok(Event {
                     id: fd,
+                    manual: Some(manual),
                 })
             },
             Err(errno) => {
                 cold_path();
-                trace!(target: "ntsync", handle=self.inner.handle.as_raw_fd(), returncode=errno as i32 ;"Failed to create event");
-                Err(Error::Unknown(errno as i32))
+                trace!(target: "ntsync", handle=self.inner.handle.as_raw_fd(), kind="event", returncode=errno as i32 ;"Failed to create event");
+                Err(create_error(errno))
+            },
+        }
+    }
+
+    /// Creates an Event like [new_event](NtSync::new_event), whose file descriptor is inherited by child processes.
+    ///
+    /// The kernel creates every object with `FD_CLOEXEC`, so the objects of [new_event](NtSync::new_event) are closed in an `exec`.
+    /// This clears the flag for code that passes the event to an child on purpose. If clearing fails the event is deleted and the error returned.
+    pub fn new_event_inheritable(&self, signaled: bool, manual: bool) -> Result<Event> {
+        let event = self.new_event(signaled, manual)?;
+        let cleared = Errno::result(unsafe { libc::fcntl(event.id, libc::F_GETFD) })
+            .and_then(|flags| Errno::result(unsafe { libc::fcntl(event.id, libc::F_SETFD, flags & !libc::FD_CLOEXEC) }));
+        if let Err(errno) = cleared {
+            cold_path();
+            debug!(target: "ntsync", handle=event.id, kind="event", returncode=errno as i32; "Failed to clear close-on-exec of the event");
+            if let Err(error) = event.delete() {
+                warn!(target: "ntsync", handle=event.id, kind="event"; "Failed to delete the event after an error: {error}");
+            }
+            return Err(Error::IOError(IOError::from_raw_os_error(errno as i32)));
+        }
+        Ok(event)
+    }
+
+    /// Creates an new Event and waits on it directly afterwards, so that the gap between creation and wait is minimal.
+    ///
+    /// The returned bool is true if the event was signaled during the wait and false if the wait timed out.
+    /// The timeout is measured with the realtime clock.
+    /// For automatic reset events an successful wait consumes the signal, manual reset events stay signaled.
+    /// If the wait fails with another error the event is deleted before the error is returned.
+    pub fn create_and_wait_event(&self, signaled: bool, manual: bool, timeout: Option<SystemTime>) -> Result<(Event, bool)> {
+        let event = self.new_event(signaled, manual)?;
+        match self.wait_any(HashSet::from([event.into()]), timeout, None, NtSyncFlags::WaitRealtime, None) {
+            Ok(_) => Ok((event, true)),
+            Err(error) if matches!(error.kind(), Error::Timeout) => Ok((event, false)),
+            Err(error) => {
+                cold_path();
+                if let Err(delete_error) = event.delete() {
+                    trace!(target: "ntsync", handle=event.id, kind="event"; "Failed to delete the event after the failed wait: {delete_error}");
+                }
+                Err(error)
             },
         }
     }
@@ -158,34 +561,27 @@ impl NtSync {
 impl Sealed for Event {}
 
 impl NTSyncObjects for Event {
-    type Status = EventStatus;
+    type Status = EventSnapshot;
 
     /// deletes the event from the program.
     /// All instances of this event are now invalid
     fn delete(self) -> Result<()> {
-        if unsafe { libc::close(self.id) } == -1 {
-            cold_path();
-            return match Errno::last() {
-                Errno::EBADF => {
-                    trace!(target: "ntsync", handle=self.id; "tried to double close an event");
-                    Err(Error::AlreadyClosed)
-                },
-                Errno::EINTR => {
-                    trace!(target: "ntsync", handle=self.id; "While closing the Event an interrupt occured");
-                    Err(Error::Interrupt)
+        #[cfg(debug_event)]
+        lost_signals().remove(&self.id);
+        #[cfg(metrics)]
+        crate::metrics::deleted(self.id);
+        crate::limit::deleted(self.id);
+        let closed = Errno::result(unsafe { libc::close(self.id) });
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::Delete {
+                    object: recorder.object(self.into()),
                 },
-                Errno::EIO => {
-                    trace!(target: "ntsync", handle=self.id; "While closing the Event an IOError occured");
-                    Err(Error::IOError(IOError::from_raw_os_error(Errno::EIO as i32)))
-                },
-                errno => {
-                    cold_path();
-                    trace!(target: "ntsync", handle=self.id; "Unexpected error while closing the event: {errno}");
-                    Err(Error::Unknown(errno as i32))
-                },
-            };
-        }
-        Ok(())
+                crate::record::Outcome::of(&closed, || crate::record::Outcome::Done),
+            )
+        });
+        crate::close_result(closed, self.id, "event")
     }
 
     fn read(&self) -> Result<Self::Status> {
@@ -194,12 +590,12 @@ impl NTSyncObjects for Event {
 }
 
 //#define NTSYNC_IOC_CREATE_EVENT         _IOW ('N', 0x87, struct ntsync_event_args)
-ioctl_write_ptr!(ntsync_create_event, NTSYNC_MAGIC, 0x87, EventStatus);
+ioctl_write_ptr!(ntsync_create_event, abi::MAGIC, abi::CREATE_EVENT, EventArgs);
 //#define NTSYNC_IOC_EVENT_SET            _IOR ('N', 0x88, __u32)
-ioctl_read!(ntsync_event_set, NTSYNC_MAGIC, 0x88, u32);
+ioctl_read!(ntsync_event_set, abi::MAGIC, abi::EVENT_SET, u32);
 //#define NTSYNC_IOC_EVENT_RESET          _IOR ('N', 0x89, __u32)
-ioctl_read!(ntsync_event_reset, NTSYNC_MAGIC, 0x89, u32);
+ioctl_read!(ntsync_event_reset, abi::MAGIC, abi::EVENT_RESET, u32);
 //#define NTSYNC_IOC_EVENT_PULSE          _IOR ('N', 0x8a, __u32)
-ioctl_read!(ntsync_event_pulse, NTSYNC_MAGIC, 0x8A, u32);
+ioctl_read!(ntsync_event_pulse, abi::MAGIC, abi::EVENT_PULSE, u32);
 //#define NTSYNC_IOC_EVENT_READ           _IOR ('N', 0x8d, struct ntsync_event_args)
-ioctl_read!(ntsync_event_read, NTSYNC_MAGIC, 0x8D, EventStatus);
+ioctl_read!(ntsync_event_read, abi::MAGIC, abi::EVENT_READ, EventArgs);
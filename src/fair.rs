@@ -0,0 +1,146 @@
+use std::{
+    collections::{
+        HashSet,
+        VecDeque,
+    },
+    sync::{
+        Mutex as StdMutex,
+        MutexGuard as StdMutexGuard,
+        PoisonError,
+    },
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use log::*;
+
+use crate::{
+    Error,
+    Event,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    Result,
+    Semaphore,
+    cold_path,
+};
+
+#[derive(Debug)]
+/// A [FairSemaphore] grants the permits in the order the waiters arrived.
+///
+/// The kernel decides on its own which waiter of an [Semaphore] is woken up.
+/// This wrapper keeps an queue of waiters in userspace, where every waiter waits on its own automatic reset [Event].
+/// When an permit is released and waiters are queued, the permit is handed directly to the first waiter instead of the semaphore.
+///
+/// The handover happens while holding the lock of the queue, so an waiter that times out either removes itself from the queue
+/// or already got the permit. No wakeup is lost this way.
+///
+/// With [acquire_with_priority](FairSemaphore::acquire_with_priority) the queue is ordered by priority first and by arrival second.
+/// The kernel has no priorities for its waiters, this ordering only exists in this userspace layer.
+/// <div class="warning">Releasing more permits than acquired is only detected by the kernel when no waiter is queued.</div>
+pub struct FairSemaphore {
+    instance: NtSync,
+    semaphore: Semaphore,
+    queue: StdMutex<VecDeque<(u32, Event)>>,
+}
+
+impl FairSemaphore {
+    fn queue(&self) -> StdMutexGuard<'_, VecDeque<(u32, Event)>> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Acquires one permit. Waiters are served in the order they called this method.
+    ///
+    /// The timeout is measured with the realtime clock. It returns [Error::Timeout] if no permit was handed to this waiter in time.
+    /// It uses the lowest priority, so waiters of [acquire_with_priority](FairSemaphore::acquire_with_priority) with an higher one are served first.
+    pub fn acquire(&self, timeout: Option<SystemTime>) -> Result<()> {
+        self.acquire_with_priority(0, timeout)
+    }
+
+    /// Acquires one permit like [acquire](FairSemaphore::acquire), but waiters with an higher `priority` are served first.
+    ///
+    /// Waiters with the same priority are served in the order they arrived. An permit that is available right away is taken
+    /// without looking at the priority, because then nobody is queued.
+    pub fn acquire_with_priority(&self, priority: u32, timeout: Option<SystemTime>) -> Result<()> {
+        let waiter = {
+            let mut queue = self.queue();
+            if queue.is_empty() {
+                match self.instance.wait_any(HashSet::from([self.semaphore.into()]), Some(UNIX_EPOCH), None, NtSyncFlags::WaitRealtime, None) {
+                    Ok(_) => return Ok(()),
+                    Err(error) if matches!(error.kind(), Error::Timeout) => {},
+                    Err(error) => return Err(error),
+                }
+            }
+            let waiter = self.instance.new_event(false, false)?;
+            let position = queue.iter().position(|(queued, _)| *queued < priority).unwrap_or(queue.len());
+            queue.insert(position, (priority, waiter));
+            waiter
+        };
+        let result = match self.instance.wait_any(HashSet::from([waiter.into()]), timeout, None, NtSyncFlags::WaitRealtime, None) {
+            Ok(_) => Ok(()),
+            Err(error) => {
+                let mut queue = self.queue();
+                match queue.iter().position(|(_, queued)| *queued == waiter) {
+                    Some(position) => {
+                        queue.remove(position);
+                        Err(error)
+                    },
+                    // The permit was handed over between the end of the wait and taking the lock
+                    None => Ok(()),
+                }
+            },
+        };
+        if let Err(error) = waiter.delete() {
+            cold_path();
+            warn!(target: "ntsync", handle=waiter.id; "Failed to delete the event of an waiter: {error}");
+        }
+        result
+    }
+
+    /// Releases `amount` permits. Queued waiters get the permits first, the rest is released to the semaphore.
+    ///
+    /// If an Error is returned, the permits that were handed to waiters before the error stay handed over.
+    /// An waiter whose signal failed stays at the front of the queue, so it does not take an permit it never got when it times out.
+    pub fn release(&self, amount: u32) -> Result<()> {
+        let mut queue = self.queue();
+        for handed in 0..amount {
+            match queue.front() {
+                Some((_, waiter)) => {
+                    waiter.signal()?;
+                    queue.pop_front();
+                },
+                None => {
+                    self.semaphore.release(amount - handed)?;
+                    break;
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// The number of threads that are currently queued.
+    pub fn waiters(&self) -> usize {
+        self.queue().len()
+    }
+}
+
+impl Drop for FairSemaphore {
+    fn drop(&mut self) {
+        if let Err(error) = self.semaphore.delete() {
+            self.instance.drop_failed(self.semaphore.id, "semaphore of an FairSemaphore", &error);
+        }
+    }
+}
+
+impl NtSync {
+    /// Creates an new [FairSemaphore] with `maximum` permits that are all available.
+    pub fn new_fair_semaphore(&self, maximum: u32) -> Result<FairSemaphore> {
+        Ok(FairSemaphore {
+            instance: self.clone(),
+            semaphore: self.new_semaphore(maximum)?,
+            queue: StdMutex::new(VecDeque::new()),
+        })
+    }
+}
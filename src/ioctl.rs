@@ -0,0 +1,53 @@
+//! Selects the backend that issues the ioctls.
+//!
+//! By default the macros of nix are used.
+//! With the `minimal` feature, or without the `nix` feature, the ioctls are issued directly with [libc::ioctl] and the request numbers are computed by [request_code](crate::abi::request_code).
+//! Both backends return the [Errno](crate::errno::Errno) of the crate, so the error handling is the same for both.
+
+#[cfg(not(minimal))]
+pub(crate) use nix::{
+    ioctl_read,
+    ioctl_readwrite,
+    ioctl_write_ptr,
+};
+
+#[cfg(minimal)]
+macro_rules! ioctl_backend {
+    ($name:ident, $direction:expr, $magic:expr, $nr:expr, $ty:ty, $pointer:ty) => {
+        /// # Safety
+        /// `data` has to point to an valid value of the type the ioctl expects and `fd` has to be an file descriptor of the ntsync driver.
+        unsafe fn $name(fd: ::libc::c_int, data: $pointer) -> $crate::errno::Result<::libc::c_int> {
+            let request = $crate::abi::request_code($direction, $magic, $nr, ::std::mem::size_of::<$ty>());
+            $crate::errno::Errno::result(unsafe { ::libc::ioctl(fd, request as _, data) })
+        }
+    };
+}
+
+#[cfg(minimal)]
+macro_rules! ioctl_read {
+    ($name:ident, $magic:expr, $nr:expr, $ty:ty) => {
+        $crate::ioctl::ioctl_backend!($name, $crate::abi::READ, $magic, $nr, $ty, *mut $ty);
+    };
+}
+
+#[cfg(minimal)]
+macro_rules! ioctl_readwrite {
+    ($name:ident, $magic:expr, $nr:expr, $ty:ty) => {
+        $crate::ioctl::ioctl_backend!($name, $crate::abi::READ | $crate::abi::WRITE, $magic, $nr, $ty, *mut $ty);
+    };
+}
+
+#[cfg(minimal)]
+macro_rules! ioctl_write_ptr {
+    ($name:ident, $magic:expr, $nr:expr, $ty:ty) => {
+        $crate::ioctl::ioctl_backend!($name, $crate::abi::WRITE, $magic, $nr, $ty, *const $ty);
+    };
+}
+
+#[cfg(minimal)]
+pub(crate) use {
+    ioctl_backend,
+    ioctl_read,
+    ioctl_readwrite,
+    ioctl_write_ptr,
+};
@@ -0,0 +1,93 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc,
+        atomic::{
+            AtomicU64,
+            Ordering,
+        },
+    },
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+    },
+};
+
+use crate::{
+    Error,
+    Event,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    Result,
+};
+
+#[derive(Debug)]
+struct LatencyProbeInner {
+    instance: NtSync,
+    event: Event,
+    origin: Instant,
+    /// The nanoseconds since `origin` at the last mark plus one, zero if it was never marked.
+    marked: AtomicU64,
+}
+
+impl Drop for LatencyProbeInner {
+    fn drop(&mut self) {
+        if let Err(error) = self.event.delete() {
+            self.instance.drop_failed(self.event.id, "event of an LatencyProbe", &error);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Measures the time from an [mark](LatencyProbe::mark) on one thread until the waiter on another thread wakes up.
+///
+/// It pairs an automatic reset [Event] with an timestamp. The signaler stores the time right before it signals the event,
+/// and the waiter reads it right after the wait returned, so the latency covers the signal ioctl, the wakeup and the scheduling of the waiter.
+/// Both use [Instant], so the accuracy is bounded by the resolution of the monotonic clock. Clones share the event and the timestamp.
+pub struct LatencyProbe {
+    inner: Arc<LatencyProbeInner>,
+}
+
+impl LatencyProbe {
+    /// Stores the current time and signals the event.
+    pub fn mark(&self) -> Result<()> {
+        let nanos = u64::try_from(self.inner.origin.elapsed().as_nanos()).unwrap_or(u64::MAX - 1);
+        self.inner.marked.store(nanos + 1, Ordering::Release);
+        self.inner.event.signal()?;
+        Ok(())
+    }
+
+    /// Waits for an mark and returns the time since it, or [None] if the wait timed out. The timeout is measured with the realtime clock.
+    pub fn wait(&self, timeout: Option<SystemTime>) -> Result<Option<Duration>> {
+        match self.inner.instance.wait_any(HashSet::from([self.inner.event.into()]), timeout, None, NtSyncFlags::WaitRealtime, None) {
+            Ok(_) => Ok(self.elapsed()),
+            Err(error) if matches!(error.kind(), Error::Timeout) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Returns the time since the last mark, or [None] if it was never marked.
+    pub fn elapsed(&self) -> Option<Duration> {
+        let now = self.inner.origin.elapsed();
+        match self.inner.marked.load(Ordering::Acquire) {
+            0 => None,
+            marked => Some(now.saturating_sub(Duration::from_nanos(marked - 1))),
+        }
+    }
+}
+
+impl NtSync {
+    /// Creates an [LatencyProbe] that is not marked yet.
+    pub fn new_latency_probe(&self) -> Result<LatencyProbe> {
+        Ok(LatencyProbe {
+            inner: Arc::new(LatencyProbeInner {
+                instance: self.clone(),
+                event: self.new_event(false, false)?,
+                origin: Instant::now(),
+                marked: AtomicU64::new(0),
+            }),
+        })
+    }
+}
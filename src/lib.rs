@@ -5,49 +5,175 @@
 #![doc = include_str!("../README.md")]
 use bitflags::bitflags;
 use derive_new::new;
+use libc::c_int;
 use log::*;
-use nix::libc::c_int;
 use std::{
+    collections::HashSet,
     fmt::Display,
     fs::{
         File,
+        OpenOptions,
         exists,
     },
+    io::Error as IOError,
+    mem::MaybeUninit,
+    num::NonZeroU32,
+    os::fd::{
+        AsRawFd,
+        FromRawFd,
+        IntoRawFd as _,
+        OwnedFd,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
     result,
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{
+            AtomicU8,
+            AtomicU64,
+        },
+    },
 };
 
+pub mod abi;
+#[cfg(debug_semaphore)]
+mod accounting;
+#[cfg(asynchronous)]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+mod asynchronous;
+mod backoff;
+mod build_info;
+#[cfg(all(mutex, semaphore))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "mutex", feature = "semaphore"))))]
+mod condvar;
+mod drop_policy;
+mod errno;
 mod error;
 mod event;
+#[cfg(semaphore)]
+#[cfg_attr(docsrs, doc(cfg(feature = "semaphore")))]
+mod fair;
+mod ioctl;
+mod latency;
+mod limit;
+#[cfg(metrics)]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+mod metrics;
+mod mirror;
 #[cfg(mutex)]
 #[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
 mod mutex;
+mod oneshot;
+mod parker;
+#[cfg(record)]
+#[cfg_attr(docsrs, doc(cfg(feature = "record")))]
+mod record;
+mod reusable_alert;
+mod self_test;
 #[cfg(semaphore)]
 #[cfg_attr(docsrs, doc(cfg(feature = "semaphore")))]
 mod semaphore;
+mod shared_event;
+mod typed_event;
 mod wait;
 
+use crate::errno::Errno;
 pub use crate::error::Error;
+pub use backoff::Backoff;
+pub use build_info::{
+    BuildInfo,
+    build_info,
+};
+#[cfg(all(mutex, semaphore))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "mutex", feature = "semaphore"))))]
+pub use condvar::Condvar;
+pub use drop_policy::DropPolicy;
+
+#[cfg(asynchronous)]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use asynchronous::{
+    ReadinessFd,
+    WaitAnyFuture,
+};
 
 #[cfg(semaphore)]
 #[cfg_attr(docsrs, doc(cfg(feature = "semaphore")))]
 pub use crate::semaphore::{
+    BinaryLockGuard,
     Semaphore,
-    SemaphoreStatus,
+    SemaphoreSnapshot,
 };
 pub use event::{
     Event,
-    EventStatus,
+    EventBuilder,
+    EventSnapshot,
+};
+#[cfg(semaphore)]
+#[cfg_attr(docsrs, doc(cfg(feature = "semaphore")))]
+pub use fair::FairSemaphore;
+pub use latency::LatencyProbe;
+#[cfg(metrics)]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+pub use metrics::{
+    DeviceSnapshot,
+    ObjectEntry,
+    ObjectKind,
+    ObjectStatus,
+    ProfiledWaitAll,
+    WaitInfo,
 };
+pub use mirror::MirrorHandle;
 #[cfg(mutex)]
 #[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
 pub use mutex::{
+    MultiGuard,
     Mutex,
-    MutexStatus,
+    MutexGuard,
+    MutexSnapshot,
+    RecoveryGuard,
+};
+pub use oneshot::OneShot;
+pub use parker::{
+    Parker,
+    Unparker,
+};
+#[cfg(record)]
+#[cfg_attr(docsrs, doc(cfg(feature = "record")))]
+pub use record::{
+    Operation,
+    Outcome,
+    Trace,
+    TraceEntry,
+};
+pub use reusable_alert::{
+    AlertReset,
+    ReusableAlert,
+};
+pub use self_test::SelfTestReport;
+pub use shared_event::SharedEvent;
+pub use typed_event::{
+    AutoEvent,
+    ManualEvent,
+};
+#[cfg(random)]
+pub use wait::JitteredTimeout;
+pub use wait::{
+    Draining,
+    IntoTimeout,
+    MAX_WAIT_COUNT,
+    RawTimeout,
+    RetryInfo,
+    TimeoutReport,
+    WaitAllStatus,
+    WaitAnyStatus,
+    WaitOutcome,
+    WaitSetProblem,
 };
 
 const DEVICE: &str = "/dev/ntsync";
-const NTSYNC_MAGIC: u8 = b'N';
 
 type Fd = c_int;
 
@@ -66,15 +192,18 @@ macro_rules! raw {
 pub(crate) use raw;
 
 #[inline(always)]
-#[cold]
+#[cfg_attr(not(no_cold_hints), cold)]
 /// Helper until cold_path is stable
 ///
 /// This helps by informing the compiler that this happens rarely and the hot path should be prioritiesed in terms of optimization
+///
+/// The `no-cold-hints` feature drops the `#[cold]` attribute, so profiles of workloads with many errors are not skewed by the biased codegen.
+/// It is only meant for profiling builds.
 pub(crate) fn cold_path() {}
 
 
 bitflags! {
-    #[derive(Debug, Default)]
+    #[derive(Debug, Default, Clone, Copy)]
     /// This helps Managing the Flags for waiting on Events.
     pub struct NtSyncFlags: u32 {
         /// This causes the Kernel to use the Realtime Clock instead of the monotonic clock.
@@ -82,8 +211,29 @@ bitflags! {
     }
 }
 
+impl NtSyncFlags {
+    /// Returns the names of the flags that are set, for example `["WaitRealtime"]`.
+    pub fn active_flags(&self) -> Vec<&'static str> {
+        self.iter_names().map(|(name, _)| name).collect()
+    }
+}
+
+impl Display for NtSyncFlags {
+    /// Writes the names of the flags that are set joined with ` | `, or nothing if no flag is set.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, (name, _)) in self.iter_names().enumerate() {
+            if index > 0 {
+                f.write_str(" | ")?;
+            }
+            f.write_str(name)?;
+        }
+        Ok(())
+    }
+}
+
 #[repr(transparent)]
-#[derive(Debug, new, Clone, Copy, PartialEq, PartialOrd, Ord, Eq, Default)]
+#[derive(Debug, new, Clone, Copy, PartialEq, PartialOrd, Ord, Eq, Hash, Default)]
+#[cfg_attr(serialize, derive(serde::Serialize))]
 /// An [OwnerId] is just an identifier for an part of the code which needs protections against parallel Access.
 ///
 /// The Kernel Module does not check if it matches something else than an number
@@ -98,16 +248,92 @@ impl OwnerId {
     }
 }
 
+impl OwnerId {
+    /// Derives the owner from the thread id of the calling thread.
+    ///
+    /// Thread ids are unique among the running threads of the system, so two threads never get the same owner.
+    /// An id can be reused after its thread exited, so an mutex abandoned by an exited thread could be unlocked by an new thread with the same id.
+    /// The kernel never assigns zero, which is the invalid owner.
+    pub fn current_thread() -> Self {
+        OwnerId(unsafe { libc::gettid() } as u32)
+    }
+}
+
 impl Display for OwnerId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
     }
 }
 
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Ord, Eq, Hash)]
+/// An [OwnerId] that is never zero.
+///
+/// Waits on an [Mutex] are rejected with an zero owner, but [OwnerId::new] and [OwnerId::default] accept it.
+/// This type enforces it when it is created, so an invalid owner is found there instead of at the wait.
+/// It converts into an [OwnerId] for the wait functions: `Some(owner.into())`.
+pub struct NonZeroOwnerId(NonZeroU32);
+
+impl NonZeroOwnerId {
+    /// Creates the owner. Returns [None] if `id` is zero.
+    pub const fn new(id: u32) -> Option<Self> {
+        match NonZeroU32::new(id) {
+            Some(id) => Some(NonZeroOwnerId(id)),
+            None => None,
+        }
+    }
+
+    /// Returns the id as number.
+    pub const fn get(&self) -> u32 {
+        self.0.get()
+    }
+
+    #[cfg(random)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "random")))]
+    /// Generates an random Owner
+    pub fn random() -> Self {
+        NonZeroOwnerId(rand::random::<NonZeroU32>())
+    }
+}
+
+impl From<NonZeroOwnerId> for OwnerId {
+    fn from(owner: NonZeroOwnerId) -> Self {
+        OwnerId(owner.get())
+    }
+}
+
+impl TryFrom<OwnerId> for NonZeroOwnerId {
+    type Error = Error;
+
+    /// Fails with [Error::InvalidValue] for the owner zero.
+    fn try_from(owner: OwnerId) -> Result<Self> {
+        NonZeroOwnerId::new(owner.0).ok_or(Error::InvalidValue)
+    }
+}
+
+impl Display for NonZeroOwnerId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 #[derive(Debug)]
 #[doc(hidden)]
 struct NtSyncInner {
     handle: File,
+    #[cfg(metrics)]
+    waits: metrics::WaitRegistry,
+    drop_policy: AtomicU8,
+    drop_failures: AtomicU64,
+    limit: Option<Arc<limit::ObjectLimit>>,
+    origin: Option<Origin>,
+}
+
+#[derive(Debug, Clone)]
+/// The path the device was opened from and the limit it was opened with, so [new_independent](NtSync::new_independent) can open it the same way.
+struct Origin {
+    path: PathBuf,
+    max_objects: Option<usize>,
 }
 
 #[derive(Debug)]
@@ -120,8 +346,32 @@ pub struct NtSync {
 
 impl NtSync {
     /// Creates an new instance of NtSync
+    ///
+    /// The device is opened read-write. Most kernels accept the ioctls on an read-only handle,
+    /// but the create ioctls write into the context of the device and an hardened kernel or an LSM policy can require write access for them.
     pub fn new() -> Result<Self> {
-        match exists(DEVICE) {
+        let origin = Origin {
+            path: PathBuf::from(DEVICE),
+            max_objects: None,
+        };
+        NtSync::open(&origin.path).map(|handle| NtSync::wrap(handle, Some(origin)))
+    }
+
+    /// Opens the device at `path` like [new](NtSync::new) and limits the instance to `max_objects` objects that are alive at the same time.
+    ///
+    /// Creating an object above the limit returns [Error::ResourceExhausted] without asking the kernel, independent of the limit of open files.
+    /// Deleting an object frees its slot, also when an wrapper like [Parker] deletes its objects while it is dropped.
+    /// [Clones](Clone) share the limit, adopted objects are not counted.
+    pub fn with_object_limit(path: impl AsRef<Path>, max_objects: usize) -> Result<Self> {
+        let origin = Origin {
+            path: path.as_ref().to_path_buf(),
+            max_objects: Some(max_objects),
+        };
+        NtSync::open(&origin.path).map(|handle| NtSync::wrap(handle, Some(origin)))
+    }
+
+    fn open(path: &Path) -> Result<File> {
+        match exists(path) {
             Ok(true) => {},
             Ok(false) => return Err(Error::NotExist),
             Err(error) => {
@@ -129,14 +379,8 @@ impl NtSync {
                 return Err(Error::IOError(error));
             },
         }
-        match File::open(DEVICE) {
-            Ok(file) => {
-                Ok(NtSync {
-                    inner: Arc::new(NtSyncInner {
-                        handle: file,
-                    }),
-                })
-            },
+        match OpenOptions::new().read(true).write(true).open(path) {
+            Ok(file) => Ok(file),
             Err(error) => {
                 cold_path();
                 trace!(target: "ntsync","Failed to open ntsync device: {error}");
@@ -144,6 +388,102 @@ impl NtSync {
             },
         }
     }
+
+    /// Creates an instance from an device that was opened elsewhere, for example by an sandbox broker that passed the file descriptor.
+    ///
+    /// The file is checked with an [ping](NtSync::ping). If it is not the ntsync device [Error::WrongObjectType] is returned.
+    pub fn from_file(file: File) -> Result<Self> {
+        let instance = NtSync::wrap(file, None);
+        match instance.ping() {
+            Ok(()) => Ok(instance),
+            Err(Error::InvalidValue) => {
+                cold_path();
+                Err(Error::WrongObjectType {
+                    expected: "ntsync device",
+                })
+            },
+            Err(Error::Unknown(errno)) if errno == Errno::ENOTTY as i32 => {
+                cold_path();
+                Err(Error::WrongObjectType {
+                    expected: "ntsync device",
+                })
+            },
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Creates an instance from the file descriptor of an device that was opened elsewhere, like [from_file](NtSync::from_file).
+    pub fn from_owned_fd(fd: OwnedFd) -> Result<Self> {
+        NtSync::from_file(File::from(fd))
+    }
+
+    fn wrap(handle: File, origin: Option<Origin>) -> Self {
+        NtSync {
+            inner: Arc::new(NtSyncInner {
+                handle,
+                #[cfg(metrics)]
+                waits: metrics::WaitRegistry::default(),
+                drop_policy: AtomicU8::new(DropPolicy::default() as u8),
+                drop_failures: AtomicU64::new(0),
+                limit: origin.as_ref().and_then(|origin| origin.max_objects).map(|max_objects| Arc::new(limit::ObjectLimit::new(max_objects))),
+                origin,
+            }),
+        }
+    }
+
+    /// Opens the device again and returns an separate instance, that shares nothing with this one.
+    ///
+    /// [Clones](Clone) share the device handle and can use each others objects. The new instance has its own context in the kernel,
+    /// so its objects can not be waited on together with the objects of this instance, see [merge](NtSync::merge).
+    /// The [DropPolicy] is not copied either.
+    ///
+    /// The device is opened from the same path as this instance. An instance from [with_object_limit](NtSync::with_object_limit) gets an own limit with the same maximum.
+    /// An instance from [from_file](NtSync::from_file) or [from_owned_fd](NtSync::from_owned_fd) has no path, so it returns [Error::NoDevicePath].
+    pub fn new_independent(&self) -> Result<NtSync> {
+        let Some(origin) = &self.inner.origin else {
+            cold_path();
+            debug!(target: "ntsync", "The device handle {} was passed in, so it can not be opened again", self.inner.handle.as_raw_fd());
+            return Err(Error::NoDevicePath);
+        };
+        NtSync::open(&origin.path).map(|handle| NtSync::wrap(handle, Some(origin.clone())))
+    }
+
+    /// Returns an instance that can wait on the objects of both instances.
+    ///
+    /// Every open of the device creates its own context in the kernel and the objects are bound to the context they were created in.
+    /// The kernel rejects waits with objects from other contexts and has no way to join two contexts, so this only works for clones of the same instance.
+    /// For independent instances it returns [Error::CrossInstance].
+    pub fn merge(&self, other: &NtSync) -> Result<NtSync> {
+        if Arc::ptr_eq(&self.inner, &other.inner) {
+            return Ok(self.clone());
+        }
+        cold_path();
+        debug!(target: "ntsync", "Can not merge the independent device handles {} and {}", self.inner.handle.as_raw_fd(), other.inner.handle.as_raw_fd());
+        Err(Error::CrossInstance)
+    }
+
+    /// Checks if the device still responds.
+    ///
+    /// It creates an Event and deletes it immediately, so no state of other objects is changed.
+    /// The probe is not leaked, because the only operation that can fail after the creation is the deletion itself.
+    pub fn ping(&self) -> Result<()> {
+        let probe = match self.new_event(false, true) {
+            Ok(event) => event,
+            Err(error) => {
+                cold_path();
+                warn!(target: "ntsync", "Device did not respond to the ping: {error}");
+                return Err(error);
+            },
+        };
+        probe.delete()
+    }
+}
+
+impl AsRawFd for NtSync {
+    /// Returns the file descriptor of the device.
+    fn as_raw_fd(&self) -> Fd {
+        self.inner.handle.as_raw_fd()
+    }
 }
 
 unsafe impl Send for NtSync {}
@@ -159,10 +499,41 @@ impl Clone for NtSync {
 }
 
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 /// An Wrapper around the different Syncronisation Primitives of this crate
 ///
-/// EventSources is an enum, so that the different types can coexist in an [HashSet](std::collections::HashSet), [Vec] or any other type dealing with them,
+/// EventSources is an enum, so that the different types can coexist in an [HashSet], [Vec] or any other type dealing with them,
+///
+/// The ordering sorts first by the kind of the object (Mutex, Semaphore, Event) and then by the file descriptor within that kind.
+/// Since the file descriptors are only valid inside one process the order is also only meaningful per process,
+/// but it can be used to establish an consistent lock order or an deterministic processing of wait sets.
+///
+/// Wait sets can be collected from converted primitives or from references to them:
+/// ```no_run
+/// # use ntsync::{Error, EventSources, NtSync, NtSyncFlags};
+/// # use std::collections::HashSet;
+/// # fn main() -> Result<(), Error> {
+/// let instance = NtSync::new()?;
+/// let event = instance.new_event(true, true)?;
+/// let semaphore = instance.new_semaphore(1)?;
+/// let sources: HashSet<EventSources> = [
+///     event.into(),
+///     semaphore.into(),
+/// ]
+/// .into_iter()
+/// .collect();
+/// instance.wait_all(sources, None, None, NtSyncFlags::empty(), None)?;
+///
+/// let events = [
+///     instance.new_event(true, false)?,
+///     event,
+/// ];
+/// let mut sources: HashSet<EventSources> = events.iter().map(EventSources::from).collect();
+/// sources.extend([EventSources::from(semaphore)]);
+/// instance.wait_any(sources, None, None, NtSyncFlags::empty(), None)?;
+/// # Ok(())
+/// # }
+/// ```
 pub enum EventSources {
     #[cfg(mutex)]
     #[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
@@ -177,7 +548,106 @@ pub enum EventSources {
     Event(event::Event),
 }
 
+/// Implements the conversion from references, so that slices of the primitives can be mapped into [EventSources].
+macro_rules! collect_sources {
+    ($type:ty) => {
+        impl From<&$type> for EventSources {
+            fn from(value: &$type) -> Self {
+                (*value).into()
+            }
+        }
+    };
+}
+
+collect_sources!(Event);
+#[cfg(mutex)]
+collect_sources!(Mutex);
+#[cfg(semaphore)]
+collect_sources!(Semaphore);
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+/// [ObjectIdentity] identifies the kernel object behind an file descriptor, so that processes can check if they use the same object.
+///
+/// It is derived from the device and inode number of the file that backs the object.
+/// The Kernel creates the objects as anonymous inodes. Depending on the kernel version all objects share the same anonymous inode,
+/// in that case objects with different identities are always different objects, but objects with the same identity are not guaranteed to be the same.
+pub struct ObjectIdentity {
+    device: u64,
+    inode: u64,
+}
+
+impl ObjectIdentity {
+    /// The device number of the file backing the object.
+    pub fn device(&self) -> u64 {
+        self.device
+    }
+
+    /// The inode number of the file backing the object.
+    pub fn inode(&self) -> u64 {
+        self.inode
+    }
+}
+
+impl Display for ObjectIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{}:{}", self.device, self.inode))
+    }
+}
+
+impl AsRawFd for EventSources {
+    fn as_raw_fd(&self) -> Fd {
+        match self {
+            #[cfg(mutex)]
+            EventSources::Mutex(mutex) => mutex.as_raw_fd(),
+            #[cfg(semaphore)]
+            EventSources::Semaphore(semaphore) => semaphore.as_raw_fd(),
+            EventSources::Event(event) => event.as_raw_fd(),
+        }
+    }
+}
+
 impl EventSources {
+    /// Returns the [ObjectIdentity] of the object, which is the same for all file descriptors of the object, even across processes.
+    pub fn identity(&self) -> Result<ObjectIdentity> {
+        let mut stat = MaybeUninit::<libc::stat>::uninit();
+        if unsafe { libc::fstat(self.as_raw_fd(), stat.as_mut_ptr()) } == -1 {
+            cold_path();
+            return match Errno::last() {
+                Errno::EBADF => Err(Error::AlreadyClosed),
+                errno => {
+                    trace!(target: "ntsync", handle=self.as_raw_fd(), returncode=errno as i32 ;"Failed to stat object");
+                    Err(Error::Unknown(errno as i32))
+                },
+            };
+        }
+        let stat = unsafe { stat.assume_init() };
+        Ok(ObjectIdentity {
+            device: stat.st_dev,
+            inode: stat.st_ino,
+        })
+    }
+
+    /// Checks if an wait on this object would be satisfied at this moment, without changing its state.
+    ///
+    /// - Events are ready when they are signaled.
+    /// - Semaphores are ready when the count is not zero.
+    /// - Mutexes are ready when they are unowned, owned by `_owner` or the previous owner died.
+    pub(crate) fn ready(&self, _owner: Option<OwnerId>) -> Result<bool> {
+        match self {
+            #[cfg(mutex)]
+            EventSources::Mutex(mutex) => {
+                match mutex.read() {
+                    Ok(status) => Ok(status.owner().is_none_or(|owner| Some(owner) == _owner)),
+                    Err(Error::OwnerDead) => Ok(true),
+                    Err(error) => Err(error),
+                }
+            },
+            #[cfg(semaphore)]
+            EventSources::Semaphore(semaphore) => Ok(semaphore.read()?.count != 0),
+            EventSources::Event(event) => Ok(event.status()?.signaled()),
+        }
+    }
+
     /// Frees the respective resource
     #[cfg_attr(feature = "mutex", doc = "- [Mutex](crate::mutex::Mutex) are unlocked.")]
     #[cfg_attr(
@@ -201,6 +671,49 @@ impl EventSources {
         };
         Ok(())
     }
+
+    /// Returns the path of the file descriptor in procfs, `/proc/self/fd/<fd>` or `/proc/<pid>/fd/<fd>` if an `pid` is given.
+    ///
+    /// Reading the link shows the name of the anonymous inode, which helps to match the objects with the output of tools like `lsof`.
+    /// The path is only meaningful on Linux with procfs mounted at `/proc`. Nothing is checked, so the path can point to nothing.
+    pub fn proc_fd_path(&self, pid: Option<u32>) -> PathBuf {
+        let process = pid.map_or_else(|| "self".to_owned(), |pid| pid.to_string());
+        PathBuf::from(format!("/proc/{process}/fd/{}", self.as_raw_fd()))
+    }
+
+    /// Deletes the object with the [delete](NTSyncObjects::delete) of its type.
+    pub fn delete(self) -> Result<()> {
+        match self {
+            #[cfg(mutex)]
+            EventSources::Mutex(mutex) => mutex.delete(),
+            #[cfg(semaphore)]
+            EventSources::Semaphore(semaphore) => semaphore.delete(),
+            EventSources::Event(event) => event.delete(),
+        }
+    }
+}
+
+impl NtSync {
+    /// Deletes all objects and returns the result for each of them, so one failure does not stop the others from being deleted.
+    ///
+    /// The objects are [Copy], so the same object can be in the list twice. Duplicates are removed before the deletion,
+    /// because closing the file descriptor again could close an unrelated file that reused the number.
+    /// The results are in the order the objects first appear in `objects`.
+    pub fn delete_all(&self, objects: Vec<EventSources>) -> Vec<(EventSources, Result<()>)> {
+        let mut seen = HashSet::with_capacity(objects.len());
+        objects
+            .into_iter()
+            .filter(|object| seen.insert(object.as_raw_fd()))
+            .map(|object| {
+                let result = object.delete();
+                if let Err(error) = &result {
+                    cold_path();
+                    debug!(target: "ntsync", handle=object.as_raw_fd(); "Failed to delete the object: {error}");
+                }
+                (object, result)
+            })
+            .collect()
+    }
 }
 
 trait Sealed {}
@@ -212,7 +725,194 @@ pub trait NTSyncObjects: Sealed + Into<EventSources> + Clone + Copy {
     type Status;
 
     /// Deletes the object. All copies of this object are now invalid and return the [Error::InvalidValue] Enum item.
+    ///
+    /// An interrupted close is reported as success, because Linux closes the file descriptor even if `close` returns `EINTR`.
+    /// Retrying it could close an unrelated file that got the same number in the meantime.
     fn delete(self) -> Result<()>;
     /// Reads the status of the object
     fn read(&self) -> Result<Self::Status>;
+
+    /// Deletes the object like [delete](NTSyncObjects::delete), but reads the status right before closing it.
+    ///
+    /// If the status could not be read the object is still deleted and [DeleteReport::status] is [None].
+    fn delete_detailed(self) -> Result<DeleteReport<Self::Status>> {
+        let fd = self.into().as_raw_fd();
+        let status = match self.read() {
+            Ok(status) => Some(status),
+            Err(error) => {
+                cold_path();
+                debug!(target: "ntsync", handle=fd; "Failed to read the status before deleting: {error}");
+                None
+            },
+        };
+        self.delete()?;
+        Ok(DeleteReport {
+            fd,
+            status,
+        })
+    }
+}
+
+#[derive(Debug)]
+/// The report of [delete_detailed](NTSyncObjects::delete_detailed), which contains the state of the object at the time it was deleted.
+pub struct DeleteReport<S> {
+    /// The file descriptor the object had.
+    pub fd: Fd,
+    /// The status right before the deletion, so it can be checked if an event was signaled, an mutex locked or an semaphore had an nonzero count.
+    pub status: Option<S>,
+}
+
+/// How often an create ioctl is repeated after it was interrupted by an signal.
+const CREATE_RETRIES: u32 = 8;
+
+/// Runs an create ioctl and repeats it if it was interrupted by an signal.
+///
+/// The kernel allocates the object only when the ioctl succeeds, so an interrupted create has no side effect and can be repeated.
+/// After [CREATE_RETRIES] interrupted attempts `EINTR` is returned to the caller.
+fn create_retrying(mut create: impl FnMut() -> crate::errno::Result<Fd>) -> crate::errno::Result<Fd> {
+    let mut attempt = 0;
+    loop {
+        match create() {
+            Err(Errno::EINTR) if attempt < CREATE_RETRIES => {
+                cold_path();
+                attempt += 1;
+                trace!(target: "ntsync", "The creation was interrupted, retrying attempt {attempt}");
+            },
+            result => return result,
+        }
+    }
+}
+
+/// Maps the errno of an failed create ioctl, shared by all object types.
+///
+/// `EMFILE` and `ENFILE` are the per-process and system wide limits of open files, each object is an file descriptor.
+/// `ENOSPC` and `EDQUOT` are returned when an cgroup or an quota limits the kernel objects, which is common in containers.
+fn create_error(errno: Errno) -> Error {
+    match errno {
+        Errno::EINVAL => Error::InvalidValue,
+        Errno::EINTR => Error::Interrupt,
+        Errno::EBADF => Error::AlreadyClosed,
+        Errno::EMFILE | Errno::ENFILE | Errno::ENOSPC | Errno::EDQUOT => {
+            debug!(target: "ntsync", "The creation hit an resource limit: {errno}");
+            Error::QuotaExceeded
+        },
+        other => Error::Unknown(other as i32),
+    }
+}
+
+/// Maps the result of closing the file descriptor of an object, shared by the [delete](NTSyncObjects::delete) of all object types.
+///
+/// Linux releases the file descriptor before close can be interrupted, so `EINTR` counts as closed and the object must not be closed again.
+fn close_result(closed: errno::Result<c_int>, handle: Fd, kind: &'static str) -> Result<()> {
+    let Err(errno) = closed else {
+        return Ok(());
+    };
+    cold_path();
+    match errno {
+        Errno::EBADF => {
+            trace!(target: "ntsync", handle=handle, kind=kind; "tried to double close an {kind}");
+            Err(Error::AlreadyClosed)
+        },
+        Errno::EINTR => {
+            trace!(target: "ntsync", handle=handle, kind=kind; "While closing the {kind} an interrupt occured, the file descriptor is closed anyway");
+            Ok(())
+        },
+        Errno::EIO => {
+            trace!(target: "ntsync", handle=handle, kind=kind; "While closing the {kind} an IOError occured");
+            Err(Error::IOError(IOError::from_raw_os_error(Errno::EIO as i32)))
+        },
+        errno => {
+            trace!(target: "ntsync", handle=handle, kind=kind; "Unexpected error while closing the {kind}: {errno}");
+            Err(Error::Unknown(errno as i32))
+        },
+    }
+}
+
+/// Wraps an received file descriptor after probing with an status read that it is an object of the expected type.
+///
+/// The kernel rejects the status read of an other object type with `EINVAL` and an file descriptor that is not an ntsync object with `ENOTTY`.
+/// On an error the file descriptor is closed.
+fn adopt<T: NTSyncObjects + FromRawFd>(fd: OwnedFd, expected: &'static str) -> Result<T> {
+    let id = fd.as_raw_fd();
+    let object = unsafe { T::from_raw_fd(id) };
+    match object.read() {
+        Ok(_) | Err(Error::OwnerDead) => {
+            let _ = fd.into_raw_fd();
+            Ok(object)
+        },
+        Err(Error::InvalidValue) => {
+            cold_path();
+            debug!(target: "ntsync", handle=id; "The file descriptor is not an {expected}");
+            Err(Error::WrongObjectType {
+                expected,
+            })
+        },
+        Err(Error::Unknown(errno)) if errno == Errno::EINVAL as i32 || errno == Errno::ENOTTY as i32 => {
+            cold_path();
+            debug!(target: "ntsync", handle=id, returncode=errno; "The file descriptor is not an {expected}");
+            Err(Error::WrongObjectType {
+                expected,
+            })
+        },
+        Err(error) => {
+            cold_path();
+            debug!(target: "ntsync", handle=id; "Failed to probe the file descriptor: {error}");
+            Err(error)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_retrying_after_interrupt() {
+        let mut calls = 0;
+        let result = create_retrying(|| {
+            calls += 1;
+            if calls == 1 {
+                Err(Errno::EINTR)
+            } else {
+                Ok(7)
+            }
+        });
+        assert_eq!(result, Ok(7));
+        assert_eq!(calls, 2, "The interrupted create was not repeated");
+    }
+
+    #[test]
+    fn create_retrying_gives_up() {
+        let mut calls = 0;
+        let result = create_retrying(|| {
+            calls += 1;
+            Err(Errno::EINTR)
+        });
+        assert_eq!(result, Err(Errno::EINTR));
+        assert_eq!(calls, CREATE_RETRIES + 1, "The create was not repeated up to the limit");
+    }
+
+    #[test]
+    fn create_error_resource_limits() {
+        for errno in [
+            Errno::EMFILE,
+            Errno::ENFILE,
+            Errno::ENOSPC,
+            Errno::EDQUOT,
+        ] {
+            assert_eq!(create_error(errno), Error::QuotaExceeded, "{errno} is not mapped to QuotaExceeded");
+        }
+        assert_eq!(create_error(Errno::EINVAL), Error::InvalidValue);
+        assert_eq!(create_error(Errno::EINTR), Error::Interrupt);
+        assert_eq!(create_error(Errno::EBADF), Error::AlreadyClosed);
+    }
+
+    #[test]
+    fn close_result_mapping() {
+        assert_eq!(close_result(Ok(0), 3, "event"), Ok(()));
+        assert_eq!(close_result(Err(Errno::EINTR), 3, "event"), Ok(()), "An interrupted close was reported as failure");
+        assert_eq!(close_result(Err(Errno::EBADF), 3, "mutex"), Err(Error::AlreadyClosed));
+        assert!(matches!(close_result(Err(Errno::EIO), 3, "semaphore"), Err(Error::IOError(error)) if error.raw_os_error() == Some(Errno::EIO as i32)));
+        assert_eq!(close_result(Err(Errno::EPERM), 3, "event"), Err(Error::Unknown(Errno::EPERM as i32)));
+    }
 }
@@ -0,0 +1,101 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        LazyLock,
+        Mutex as StdMutex,
+        MutexGuard as StdMutexGuard,
+        PoisonError,
+        atomic::{
+            AtomicBool,
+            AtomicUsize,
+            Ordering,
+        },
+    },
+};
+
+use log::*;
+
+use crate::{
+    Error,
+    Fd,
+    NtSync,
+    Result,
+    cold_path,
+    create_retrying,
+};
+
+#[derive(Debug)]
+/// The number of objects an instance from [with_object_limit](NtSync::with_object_limit) may have alive at the same time.
+pub(crate) struct ObjectLimit {
+    max: usize,
+    live: AtomicUsize,
+}
+
+impl ObjectLimit {
+    pub(crate) fn new(max: usize) -> Self {
+        ObjectLimit {
+            max,
+            live: AtomicUsize::new(0),
+        }
+    }
+
+    /// Takes one slot, so concurrent creates can not exceed the limit together.
+    fn reserve(&self) -> bool {
+        self.live.fetch_update(Ordering::AcqRel, Ordering::Acquire, |live| (live < self.max).then_some(live + 1)).is_ok()
+    }
+
+    fn release(&self) {
+        let _ = self.live.fetch_update(Ordering::AcqRel, Ordering::Acquire, |live| live.checked_sub(1));
+    }
+}
+
+/// Set once the first limited object was created, so the deletes of unlimited instances skip the lock.
+static USED: AtomicBool = AtomicBool::new(false);
+
+/// The objects of limited instances that are not deleted yet, with the limit they count against.
+static LIMITED: LazyLock<StdMutex<HashMap<Fd, Arc<ObjectLimit>>>> = LazyLock::new(StdMutex::default);
+
+fn limited() -> StdMutexGuard<'static, HashMap<Fd, Arc<ObjectLimit>>> {
+    LIMITED.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Gives the slot of an deleted object back to the limit of its instance.
+pub(crate) fn deleted(fd: Fd) {
+    if !USED.load(Ordering::Acquire) {
+        return;
+    }
+    if let Some(limit) = limited().remove(&fd) {
+        limit.release();
+    }
+}
+
+impl NtSync {
+    /// Runs the create ioctl with [create_retrying] and counts the object against the limit of the instance.
+    ///
+    /// Returns [Error::ResourceExhausted] without calling the kernel if the limit is reached. The slot is given back if the kernel fails.
+    pub(crate) fn create_limited(&self, create: impl FnMut() -> crate::errno::Result<Fd>) -> Result<crate::errno::Result<Fd>> {
+        let Some(limit) = &self.inner.limit else {
+            return Ok(create_retrying(create));
+        };
+        if !limit.reserve() {
+            cold_path();
+            debug!(target: "ntsync", "The limit of {} objects is reached", limit.max);
+            return Err(Error::ResourceExhausted);
+        }
+        let result = create_retrying(create);
+        match result {
+            Ok(fd) => {
+                USED.store(true, Ordering::Release);
+                limited().insert(fd, Arc::clone(limit));
+            },
+            Err(_) => limit.release(),
+        }
+        Ok(result)
+    }
+
+    /// Returns the number of objects this instance has alive and its limit, or [None] if it was not created with [with_object_limit](NtSync::with_object_limit).
+    pub fn object_limit(&self) -> Option<(usize, usize)> {
+        self.inner.limit.as_ref().map(|limit| (limit.live.load(Ordering::Acquire), limit.max))
+    }
+}
@@ -0,0 +1,361 @@
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+    },
+    os::fd::AsRawFd,
+    result,
+    sync::{
+        LazyLock,
+        Mutex as StdMutex,
+        MutexGuard as StdMutexGuard,
+        PoisonError,
+        atomic::{
+            AtomicBool,
+            AtomicU64,
+            Ordering,
+        },
+    },
+    thread::{
+        self,
+        Builder,
+    },
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+    },
+};
+
+use log::*;
+
+#[cfg(mutex)]
+use crate::MutexSnapshot;
+#[cfg(any(mutex, semaphore))]
+use crate::NTSyncObjects as _;
+#[cfg(semaphore)]
+use crate::SemaphoreSnapshot;
+use crate::{
+    Backoff,
+    Error,
+    Event,
+    EventSnapshot,
+    EventSources,
+    Fd,
+    NtSync,
+    NtSyncFlags,
+    OwnerId,
+    Result,
+    WaitAllStatus,
+    cold_path,
+};
+
+#[derive(Debug, Clone)]
+/// Describes an wait that is currently blocked in the kernel.
+pub struct WaitInfo {
+    /// The name of the thread that started the wait, if it has one.
+    pub thread: Option<String>,
+    /// The objects that are waited on.
+    pub objects: Vec<EventSources>,
+    /// The owner that was used for the wait.
+    pub owner: Option<OwnerId>,
+    /// true if the wait was started with [wait_all](NtSync::wait_all), false for [wait_any](NtSync::wait_any).
+    pub all: bool,
+    /// The moment the wait was started.
+    pub started: Instant,
+}
+
+#[derive(Debug, Clone)]
+/// The result of [wait_all_profiled](NtSync::wait_all_profiled).
+pub struct ProfiledWaitAll {
+    /// The status of the wait itself.
+    pub status: WaitAllStatus,
+    /// For each object the time after the start of the wait, at which the sampling saw it ready for the first time.
+    /// It is [None] if the sampling never saw the object ready before the wait returned.
+    pub ready_after: Vec<(EventSources, Option<Duration>)>,
+    /// The object that became ready as the last one. If multiple objects were never seen ready, it is one of them.
+    pub bottleneck: Option<EventSources>,
+}
+
+#[derive(Debug, Default)]
+/// Keeps track of the waits that are currently in progress.
+///
+/// The lock is only held to insert or remove an entry, so it does not block while the kernel waits.
+pub(crate) struct WaitRegistry {
+    next: AtomicU64,
+    waits: StdMutex<HashMap<u64, WaitInfo>>,
+}
+
+/// The number of waits that are blocked on each event, shared by all instances of the process.
+static EVENT_WAITERS: LazyLock<StdMutex<HashMap<Fd, usize>>> = LazyLock::new(StdMutex::default);
+
+fn event_waiters() -> StdMutexGuard<'static, HashMap<Fd, usize>> {
+    EVENT_WAITERS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The objects that were created or adopted through this crate and not deleted yet, with the file descriptor of the device they belong to.
+static OBJECTS: LazyLock<StdMutex<HashMap<Fd, (Fd, EventSources)>>> = LazyLock::new(StdMutex::default);
+
+fn objects() -> StdMutexGuard<'static, HashMap<Fd, (Fd, EventSources)>> {
+    OBJECTS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Records an object that was created or adopted on `device`.
+pub(crate) fn created(device: Fd, object: EventSources) {
+    objects().insert(object.as_raw_fd(), (device, object));
+}
+
+/// Stops tracking an deleted object, so the file descriptor can be reused.
+pub(crate) fn deleted(fd: Fd) {
+    objects().remove(&fd);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(serialize, derive(serde::Serialize))]
+/// The type of an object in an [DeviceSnapshot].
+pub enum ObjectKind {
+    /// An [Event](crate::Event).
+    Event,
+    /// An [Semaphore](crate::Semaphore).
+    Semaphore,
+    /// An [Mutex](crate::Mutex).
+    Mutex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(serialize, derive(serde::Serialize))]
+/// The status of an object at the moment of the [snapshot](NtSync::snapshot).
+pub enum ObjectStatus {
+    /// The status of an event.
+    Event(EventSnapshot),
+    #[cfg(semaphore)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "semaphore")))]
+    /// The status of an semaphore.
+    Semaphore(SemaphoreSnapshot),
+    #[cfg(mutex)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
+    /// The status of an mutex.
+    Mutex(MutexSnapshot),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(serialize, derive(serde::Serialize))]
+/// One object of an [DeviceSnapshot].
+pub struct ObjectEntry {
+    /// The type of the object.
+    pub kind: ObjectKind,
+    /// The file descriptor of the object.
+    pub fd: Fd,
+    /// The status of the object, or the message of the error if it could not be read.
+    pub status: result::Result<ObjectStatus, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(serialize, derive(serde::Serialize))]
+/// The state of all objects of an instance, returned by [snapshot](NtSync::snapshot).
+///
+/// With the `serde` feature it can be serialized, for example to dump it into an log or an admin endpoint.
+pub struct DeviceSnapshot {
+    /// The file descriptor of the device.
+    pub device: Fd,
+    /// The objects sorted by their file descriptor.
+    pub objects: Vec<ObjectEntry>,
+}
+
+impl WaitRegistry {
+    fn waits(&self) -> StdMutexGuard<'_, HashMap<u64, WaitInfo>> {
+        self.waits.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Records the wait until the returned [Registration] is dropped.
+    pub(crate) fn register(&self, objects: &[EventSources], owner: Option<OwnerId>, all: bool) -> Registration<'_> {
+        let id = self.next.fetch_add(1, Ordering::Relaxed);
+        let info = WaitInfo {
+            thread: thread::current().name().map(str::to_owned),
+            objects: objects.to_vec(),
+            owner,
+            all,
+            started: Instant::now(),
+        };
+        let mut waiters = event_waiters();
+        for object in objects {
+            match object {
+                EventSources::Event(event) => *waiters.entry(event.id).or_default() += 1,
+                #[cfg(semaphore)]
+                EventSources::Semaphore(_) => {},
+                #[cfg(mutex)]
+                EventSources::Mutex(_) => {},
+            }
+        }
+        drop(waiters);
+        self.waits().insert(id, info);
+        Registration {
+            registry: self,
+            id,
+        }
+    }
+}
+
+/// Removes the wait from the [WaitRegistry] when it is dropped.
+pub(crate) struct Registration<'registry> {
+    registry: &'registry WaitRegistry,
+    id: u64,
+}
+
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        let Some(info) = self.registry.waits().remove(&self.id) else {
+            return;
+        };
+        let mut waiters = event_waiters();
+        for object in info.objects {
+            let id = match object {
+                EventSources::Event(event) => event.id,
+                #[cfg(semaphore)]
+                EventSources::Semaphore(_) => continue,
+                #[cfg(mutex)]
+                EventSources::Mutex(_) => continue,
+            };
+            if let Some(count) = waiters.get_mut(&id) {
+                *count -= 1;
+                if *count == 0 {
+                    waiters.remove(&id);
+                }
+            }
+        }
+    }
+}
+
+impl Event {
+    /// Returns how many waits are blocked on this event right now.
+    ///
+    /// Only waits of this process that were issued through this crate are counted, waits of other processes on the same event are not visible.
+    /// The number can change right after it was read, so it is only an hint, for example to skip an [signal](Event::signal) nobody waits for.
+    pub fn approx_waiters(&self) -> usize {
+        event_waiters().get(&self.id).copied().unwrap_or(0)
+    }
+
+    /// Signals the event like [signal](Event::signal) and returns the previous state with the number of waits that were blocked on it.
+    ///
+    /// The waiters are counted like [approx_waiters](Event::approx_waiters) right before the signal, so waits that start or time out in between are miscounted.
+    /// An count of 0 means that the signal most likely went to nobody, an automatic reset event then stays signaled for the next waiter.
+    pub fn signal_with_waiters(&self) -> Result<(bool, usize)> {
+        let waiters = self.approx_waiters();
+        let signaled = self.signal()?;
+        if waiters == 0 {
+            trace!(target: "ntsync", handle=self.id, kind="event"; "Signaled the event without any waiter");
+        }
+        Ok((signaled, waiters))
+    }
+}
+
+impl NtSync {
+    /// Reads the status of every object that was created or adopted with this instance and not deleted yet.
+    ///
+    /// Objects are tracked by the file descriptor, so objects that were wrapped with `from_raw_fd` or closed without [delete](crate::NTSyncObjects::delete) are not seen correctly.
+    /// An failed read does not abort the snapshot, its error is recorded in the entry of the object.
+    /// The objects are read one after another, so the snapshot is not atomic.
+    pub fn snapshot(&self) -> DeviceSnapshot {
+        let device = self.inner.handle.as_raw_fd();
+        let mut sources: Vec<EventSources> = objects().values().filter(|(owner, _)| *owner == device).map(|(_, object)| *object).collect();
+        sources.sort_by_key(AsRawFd::as_raw_fd);
+        let objects = sources
+            .into_iter()
+            .map(|source| {
+                let (kind, status) = match source {
+                    EventSources::Event(event) => (ObjectKind::Event, event.status().map(ObjectStatus::Event)),
+                    #[cfg(semaphore)]
+                    EventSources::Semaphore(semaphore) => (ObjectKind::Semaphore, semaphore.read().map(ObjectStatus::Semaphore)),
+                    #[cfg(mutex)]
+                    EventSources::Mutex(mutex) => (ObjectKind::Mutex, mutex.read().map(ObjectStatus::Mutex)),
+                };
+                ObjectEntry {
+                    kind,
+                    fd: source.as_raw_fd(),
+                    status: status.map_err(|error| {
+                        cold_path();
+                        debug!(target: "ntsync", handle=source.as_raw_fd(); "Failed to read the object for the snapshot: {error}");
+                        error.to_string()
+                    }),
+                }
+            })
+            .collect();
+        DeviceSnapshot {
+            device,
+            objects,
+        }
+    }
+
+    /// Returns all waits of this instance that are currently in progress, sorted by the time they started.
+    ///
+    /// This can be used to find deadlocks, for example by dumping it from an signal handler or an admin endpoint.
+    pub fn in_flight_waits(&self) -> Vec<WaitInfo> {
+        let mut waits: Vec<WaitInfo> = self.inner.waits.waits().values().cloned().collect();
+        waits.sort_by_key(|info| info.started);
+        waits
+    }
+
+    /// Works like [wait_all](NtSync::wait_all), but samples the readiness of every object while the wait is blocked.
+    ///
+    /// The delay between the samples follows `interval`, an [Duration] samples at an fixed interval.
+    /// With an [Backoff] the delay grows while no further object becomes ready and restarts when one does.
+    ///
+    /// The kernel does not report which object was the last to become ready, so this is only an approximation.
+    /// The object that satisfied the wait is often never seen ready by the sampling, because the wait consumes it immediately.
+    /// The sampling runs in an additional thread and reads the status of every object each interval, which adds overhead.
+    pub fn wait_all_profiled(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: Option<SystemTime>,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+        interval: impl Into<Backoff>,
+    ) -> Result<ProfiledWaitAll> {
+        let backoff = interval.into();
+        let objects: Vec<EventSources> = sources.iter().copied().collect();
+        let done = AtomicBool::new(false);
+        let started = Instant::now();
+        let (status, ready_after) = thread::scope(|scope| -> Result<(WaitAllStatus, Vec<Option<Duration>>)> {
+            let sampler = match Builder::new().name("ntsync sampler".to_owned()).spawn_scoped(scope, || {
+                let mut ready_after = vec![None; objects.len()];
+                let mut delays = backoff.delays();
+                while !done.load(Ordering::Acquire) {
+                    let mut changed = false;
+                    for (object, ready) in objects.iter().zip(ready_after.iter_mut()) {
+                        if ready.is_none() && object.ready(owner).unwrap_or(false) {
+                            *ready = Some(started.elapsed());
+                            changed = true;
+                        }
+                    }
+                    if changed {
+                        delays = backoff.delays();
+                    }
+                    thread::sleep(delays.next().unwrap_or(backoff.max));
+                }
+                ready_after
+            }) {
+                Ok(sampler) => sampler,
+                Err(error) => {
+                    cold_path();
+                    warn!(target: "ntsync", "Failed to start the sampling thread: {error}");
+                    return Err(Error::IOError(error));
+                },
+            };
+            let status = self.wait_all(sources, timeout, owner, flags, alert);
+            done.store(true, Ordering::Release);
+            let ready_after = sampler.join().unwrap_or_else(|_| {
+                cold_path();
+                warn!(target: "ntsync", "The sampling thread panicked");
+                vec![None; objects.len()]
+            });
+            Ok((status?, ready_after))
+        })?;
+        let bottleneck = objects.iter().zip(&ready_after).max_by_key(|(_, ready)| ready.unwrap_or(Duration::MAX)).map(|(object, _)| *object);
+        Ok(ProfiledWaitAll {
+            status,
+            ready_after: objects.into_iter().zip(ready_after).collect(),
+            bottleneck,
+        })
+    }
+}
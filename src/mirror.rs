@@ -0,0 +1,133 @@
+use std::{
+    collections::HashSet,
+    thread::{
+        Builder,
+        JoinHandle,
+    },
+    time::Duration,
+};
+
+use log::*;
+
+use crate::{
+    Backoff,
+    Error,
+    Event,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    Result,
+    cold_path,
+};
+
+/// The polling of an signaled manual reset source until it is reset.
+const RESET_POLL: Backoff = Backoff {
+    initial: Duration::from_millis(1),
+    max: Duration::from_millis(50),
+    factor: 2,
+};
+
+#[derive(Debug)]
+/// Stops the helper thread of an [mirror](Event::mirror) when it is dropped and waits until it exited.
+///
+/// The mirror event itself is not deleted, it belongs to the caller.
+pub struct MirrorHandle {
+    stop: Event,
+    helper: Option<JoinHandle<()>>,
+}
+
+impl Drop for MirrorHandle {
+    fn drop(&mut self) {
+        if let Err(error) = self.stop.signal() {
+            cold_path();
+            warn!(target: "ntsync", handle=self.stop.id; "Failed to stop the helper thread of an mirror: {error}");
+            return;
+        }
+        if let Some(helper) = self.helper.take() &&
+            helper.join().is_err()
+        {
+            cold_path();
+            warn!(target: "ntsync", "The helper thread of an mirror panicked");
+        }
+        if let Err(error) = self.stop.delete() {
+            cold_path();
+            warn!(target: "ntsync", handle=self.stop.id; "Failed to delete the stop event of an mirror: {error}");
+        }
+    }
+}
+
+/// Forwards every signal of `source` to `mirror` until `stop` is signaled.
+fn forward(instance: &NtSync, source: Event, mirror: Event, stop: Event, manual: bool) -> Result<()> {
+    loop {
+        if instance.wait_any(HashSet::from([source.into()]), None, None, NtSyncFlags::empty(), Some(stop))?.alerted {
+            return Ok(());
+        }
+        mirror.signal()?;
+        if !manual {
+            continue;
+        }
+        // An manual reset source stays signaled, so it is forwarded again only after it was reset.
+        let mut delays = RESET_POLL.delays();
+        while source.status()?.signaled() {
+            match instance.wait_any_for(HashSet::from([stop.into()]), delays.next(), None, NtSyncFlags::empty(), None) {
+                Ok(_) => return Ok(()),
+                Err(error) if matches!(error.kind(), Error::Timeout) => {},
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+impl Event {
+    /// Creates an new event of the same reset mode, that is signaled each time this event is signaled.
+    ///
+    /// The mirror can be reset independently, so one signal can be fanned out to events that are consumed by different parts of the program.
+    /// An helper thread forwards the signals, so the mirror is signaled shortly after the source and each mirror costs an thread and its stack.
+    /// The helper waits on the source, so an signal of an automatic reset source is consumed by the helper and only the mirror stays signaled.
+    /// An signaled manual reset source is polled until it is reset, before its next signal is forwarded.
+    ///
+    /// Dropping the [MirrorHandle] stops the forwarding. The returned event has to be deleted by the caller after that.
+    pub fn mirror(&self, instance: &NtSync) -> Result<(Event, MirrorHandle)> {
+        let manual = self.status()?.manual_reset();
+        let mirror = instance.new_event(false, manual)?;
+        let stop = match instance.new_event(false, true) {
+            Ok(stop) => stop,
+            Err(error) => {
+                cold_path();
+                if let Err(error) = mirror.delete() {
+                    warn!(target: "ntsync", handle=mirror.id; "Failed to delete the mirror event: {error}");
+                }
+                return Err(error);
+            },
+        };
+        let (instance, source) = (instance.clone(), *self);
+        let helper = Builder::new().name("ntsync mirror".to_owned()).spawn(move || {
+            if let Err(error) = forward(&instance, source, mirror, stop, manual) {
+                cold_path();
+                warn!(target: "ntsync", handle=source.id; "The mirror of the event stopped: {error}");
+            }
+        });
+        match helper {
+            Ok(helper) => {
+                Ok((
+                    mirror,
+                    MirrorHandle {
+                        stop,
+                        helper: Some(helper),
+                    },
+                ))
+            },
+            Err(error) => {
+                cold_path();
+                for event in [
+                    stop, mirror,
+                ] {
+                    if let Err(error) = event.delete() {
+                        warn!(target: "ntsync", handle=event.id; "Failed to delete the event of the mirror: {error}");
+                    }
+                }
+                Err(Error::IOError(error))
+            },
+        }
+    }
+}
@@ -1,50 +1,76 @@
 use std::{
-    io,
-    os::fd::AsRawFd as _,
+    collections::HashSet,
+    mem,
+    os::fd::{
+        AsRawFd,
+        FromRawFd,
+        OwnedFd,
+    },
+    result,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
 };
 
+use crate::errno::Errno;
 use derive_new::new;
 use log::*;
-use nix::{
-    errno::Errno,
-    ioctl_read,
-    ioctl_readwrite,
-    ioctl_write_ptr,
-    libc,
-};
 
 use crate::{
     Error,
     EventSources,
     Fd,
-    NTSYNC_MAGIC,
     NTSyncObjects,
     NtSync,
+    NtSyncFlags,
     OwnerId,
     Result,
     Sealed,
+    abi,
+    adopt,
     cold_path,
+    create_error,
+    ioctl::{
+        ioctl_read,
+        ioctl_readwrite,
+        ioctl_write_ptr,
+    },
     raw,
 };
 
 #[repr(C)]
 #[derive(Debug, new, Default)]
 #[new(visibility = "pub(crate)")]
-/// Mutex Status is the Representation of the Status of the mutex at point of the query
-pub struct MutexStatus {
+/// The arguments of the mutex ioctls, `struct ntsync_mutex_args` of the kernel.
+pub(crate) struct MutexArgs {
     owner: OwnerId,
-    /// This is how deep an thread has relocked the mutex again(mutiple [wait_any](NtSync::wait_any) or [wait_all](NtSync::wait_all) calls without unlocking it.)
     #[new(value = "0")]
     count: u32,
 }
 
-impl MutexStatus {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(serialize, derive(serde::Serialize))]
+/// Mutex Snapshot is the Representation of the Status of the mutex at point of the query
+pub struct MutexSnapshot {
+    owner: Option<OwnerId>,
+    /// This is how deep an thread has relocked the mutex again(mutiple [wait_any](NtSync::wait_any) or [wait_all](NtSync::wait_all) calls without unlocking it.)
+    count: u32,
+}
+
+impl MutexSnapshot {
     /// The current Owner of the Mutex
     pub fn owner(&self) -> Option<OwnerId> {
-        if self.owner.0 == 0 {
-            return None;
-        }
-        Some(self.owner)
+        self.owner
+    }
+
+    /// How many times the current owner has locked the Mutex.
+    ///
+    /// Each successful [wait_any](NtSync::wait_any) or [wait_all](NtSync::wait_all) of the owner increments it
+    /// and each [unlock](Mutex::unlock) decrements it. The owner is released when it reaches 0.
+    /// An freshly created Mutex has no owner and an count of 0.
+    pub fn recursion_count(&self) -> u32 {
+        self.count
     }
 
     /// how many times the current owner has locked the Mutex.
@@ -57,10 +83,21 @@ impl MutexStatus {
     }
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+impl From<MutexArgs> for MutexSnapshot {
+    fn from(args: MutexArgs) -> Self {
+        MutexSnapshot {
+            owner: (args.owner.0 != 0).then_some(args.owner),
+            count: args.count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 /// An Mutex similar to [std::sync::Mutex], but it can't store Data.
 ///
 /// On its own it can only be unlocked. The Locking is done in the [wait_any](NtSync::wait_any) or [wait_all](NtSync::wait_all) calls.
+///
+/// The ordering is by the file descriptor, so it is only meaningful inside the current process.
 pub struct Mutex {
     pub(crate) id: Fd,
 }
@@ -74,39 +111,154 @@ impl From<Mutex> for EventSources {
     }
 }
 
+impl AsRawFd for Mutex {
+    fn as_raw_fd(&self) -> Fd {
+        self.id
+    }
+}
+
+impl FromRawFd for Mutex {
+    /// Wraps an file descriptor that was received from another part of the program or another process.
+    ///
+    /// # Safety
+    /// The file descriptor must be an mutex of the ntsync device, otherwise all operations fail.
+    unsafe fn from_raw_fd(fd: Fd) -> Self {
+        Mutex {
+            id: fd,
+        }
+    }
+}
+
 impl Mutex {
-    /// unlocks the Mutex, if its the wrong owner then it fails with [PermissionDenied](crate::error::Error::PermissionDenied)
+    /// The deepest recursion the kernel allows for an owner.
+    ///
+    /// The kernel stores the depth as `u32` and treats an mutex at this depth as not acquirable for its owner,
+    /// so another wait of the owner blocks instead of wrapping around. [lock_recursive](NtSync::lock_recursive) returns [Error::DepthExceeded] instead.
+    pub const fn max_depth() -> u32 {
+        u32::MAX
+    }
+
+    /// Locks the mutex for `owner`, but fails with [Error::WouldRecurse] if the owner already holds it. The timeout is measured with the realtime clock.
+    ///
+    /// The kernel always allows an owner to lock an mutex again, which breaks algorithms that expect an lock to not be reentrant.
+    /// The mutex is read before the wait, so there is an window between the check and the wait.
+    /// It only matters if the same owner is used by multiple threads, because other owners can not make the mutex held by `owner`.
+    pub fn lock_non_recursive(&self, instance: &NtSync, owner: OwnerId, timeout: Option<SystemTime>) -> Result<()> {
+        match self.read() {
+            Ok(status) if status.owner() == Some(owner) => {
+                cold_path();
+                debug!(target: "ntsync", handle=self.id, kind="mutex"; "The owner {owner} already holds the mutex");
+                return Err(Error::WouldRecurse);
+            },
+            Ok(_) | Err(Error::OwnerDead) => {},
+            Err(error) => return Err(error),
+        }
+        instance.wait_any(HashSet::from([(*self).into()]), timeout, Some(owner), NtSyncFlags::WaitRealtime, None)?;
+        Ok(())
+    }
+
+    /// Locks the mutex for `owner` only if nobody holds it, including `owner` itself. Returns `false` if it is held.
+    ///
+    /// The mutex is read before the lock is attempted, so another owner can lock it in between, then it also returns `false`.
+    /// It never blocks. An abandoned mutex counts as free and the lock returns [Error::OwnerDead] like an wait does.
+    pub fn lock_if_free(&self, instance: &NtSync, owner: OwnerId) -> Result<bool> {
+        match self.read() {
+            Ok(status) if status.owner().is_some() => {
+                trace!(target: "ntsync", handle=self.id, kind="mutex"; "The mutex is held by {:?}", status.owner());
+                return Ok(false);
+            },
+            Ok(_) | Err(Error::OwnerDead) => {},
+            Err(error) => return Err(error),
+        }
+        match instance.wait_any(HashSet::from([(*self).into()]), Some(UNIX_EPOCH), Some(owner), NtSyncFlags::WaitRealtime, None) {
+            Ok(_) => Ok(true),
+            Err(error) if matches!(error.kind(), Error::Timeout) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Returns true if `owner` could acquire the mutex right now, because it is free or already held by `owner` itself.
+    ///
+    /// The kernel lets an owner lock its mutex again, so the answer depends on who asks, unlike the status of an event or an semaphore.
+    /// An abandoned mutex counts as acquirable, the wait then returns [Error::OwnerDead]. An owner at [max_depth](Mutex::max_depth) can not lock it again.
+    /// This is only an snapshot, another owner can lock or unlock the mutex between the read and an later wait.
+    pub fn acquirable_by(&self, owner: OwnerId) -> Result<bool> {
+        match self.read() {
+            Ok(status) => {
+                Ok(match status.owner() {
+                    None => true,
+                    Some(holder) => holder == owner && status.recursion_count() < Mutex::max_depth(),
+                })
+            },
+            Err(Error::OwnerDead) => Ok(true),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// unlocks the Mutex, if its the wrong owner then it fails with [WrongOwner](crate::error::Error::WrongOwner)
+    ///
+    /// The actual owner in the error is read after the unlock failed, so it can already be outdated.
     pub fn unlock(&self, owner: OwnerId) -> Result<()> {
-        let mut args = MutexStatus::new(owner);
-        match unsafe { ntsync_mutex_unlock(self.id, raw!(mut args: MutexStatus)) } {
+        let mut args = MutexArgs::new(owner);
+        let result = unsafe { ntsync_mutex_unlock(self.id, raw!(mut args: MutexArgs)) };
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::UnlockMutex {
+                    object: recorder.object((*self).into()),
+                    owner: owner.0,
+                },
+                crate::record::Outcome::of(&result, || crate::record::Outcome::Value(args.count)),
+            )
+        });
+        let outcome = match result {
             Ok(_) => Ok(()),
             Err(Errno::EBADF) => Err(Error::AlreadyClosed),
             Err(errno) => {
                 cold_path();
                 match errno {
                     Errno::EINVAL => Err(Error::InvalidValue),
-                    Errno::EPERM => Err(Error::PermissionDenied),
+                    Errno::EPERM => {
+                        let actual = self.read().ok().and_then(|status| status.owner());
+                        debug!(target: "ntsync", handle=self.id, kind="mutex"; "The mutex is owned by {actual:?}, not by {owner}");
+                        Err(Error::WrongOwner {
+                            expected: owner,
+                            actual,
+                        })
+                    },
                     other => {
                         cold_path();
                         Err(Error::Unknown(other as i32))
                     },
                 }
             },
-        }
+        };
+        outcome.map_err(|error| error.with_context("unlock"))
     }
 
     /// Forcibly unlocks the Mutex.
     pub fn kill(&self, owner: OwnerId) -> Result<()> {
         let id = owner.0;
-        match unsafe { ntsync_mutex_kill(self.id, raw!(const id: u32)) } {
+        let result = unsafe { ntsync_mutex_kill(self.id, raw!(const id: u32)) };
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::KillMutex {
+                    object: recorder.object((*self).into()),
+                    owner: id,
+                },
+                crate::record::Outcome::of(&result, || crate::record::Outcome::Done),
+            )
+        });
+        let outcome = match result {
             Ok(_) => {
-                error!(target: "ntsync", "Mutex {} was killed.", self.id);
+                error!(target: "ntsync", handle=self.id, kind="mutex"; "Mutex was killed.");
                 Ok(())
             },
             Err(Errno::EBADF) => Err(Error::AlreadyClosed),
             Err(errno) => {
                 cold_path();
-                error!(target: "ntsync", "Wanted to kill Mutex {}, but failed", self.id);
+                error!(target: "ntsync", handle=self.id, kind="mutex"; "Wanted to kill the Mutex, but failed");
                 match errno {
                     Errno::EINVAL => Err(Error::InvalidValue),
                     Errno::EPERM => Err(Error::PermissionDenied),
@@ -116,74 +268,307 @@ impl Mutex {
                     },
                 }
             },
+        };
+        outcome.map_err(|error| error.with_context("kill"))
+    }
+}
+
+#[derive(Debug)]
+#[must_use = "the mutex is unlocked when the guard is dropped"]
+/// Holds an [Mutex] locked by [lock_mutex](NtSync::lock_mutex) and unlocks it when it is dropped.
+pub struct MutexGuard {
+    pub(crate) mutex: Mutex,
+    pub(crate) owner: OwnerId,
+}
+
+impl MutexGuard {
+    /// Returns the locked mutex.
+    pub fn mutex(&self) -> Mutex {
+        self.mutex
+    }
+
+    /// Returns the owner that holds the mutex.
+    pub fn owner(&self) -> OwnerId {
+        self.owner
+    }
+}
+
+impl Drop for MutexGuard {
+    fn drop(&mut self) {
+        if let Err(error) = self.mutex.unlock(self.owner) {
+            cold_path();
+            warn!(target: "ntsync", handle=self.mutex.id, kind="mutex"; "Failed to unlock the mutex of an guard: {error}");
+        }
+    }
+}
+
+#[derive(Debug)]
+#[must_use = "an abandoned mutex has to be marked consistent or abandoned again"]
+/// Holds an abandoned [Mutex] locked by [lock_mutex_recoverable](NtSync::lock_mutex_recoverable), like the [PoisonError](std::sync::PoisonError) of the std.
+///
+/// The previous owner was killed while it held the mutex, so the state it protects can be inconsistent.
+/// The caller decides with [mark_consistent](RecoveryGuard::mark_consistent) or [abandon](RecoveryGuard::abandon) how to continue.
+/// If it is dropped without an decision, an warning is logged and the mutex is abandoned again.
+pub struct RecoveryGuard {
+    mutex: Mutex,
+    owner: OwnerId,
+}
+
+impl RecoveryGuard {
+    /// Returns the locked mutex.
+    pub fn mutex(&self) -> Mutex {
+        self.mutex
+    }
+
+    /// Returns the owner that holds the mutex.
+    pub fn owner(&self) -> OwnerId {
+        self.owner
+    }
+
+    /// Accepts the protected state as valid and continues with an normal guard. The following owners do not see the mutex as abandoned.
+    pub fn mark_consistent(self) -> MutexGuard {
+        let guard = MutexGuard {
+            mutex: self.mutex,
+            owner: self.owner,
+        };
+        mem::forget(self);
+        guard
+    }
+
+    /// Releases the mutex without claiming that the state is consistent, so the next owner gets it as abandoned again.
+    ///
+    /// The mutex is killed with the own owner, which the kernel reports as [Error::OwnerDead] to the next waiter.
+    pub fn abandon(self) -> Result<()> {
+        let (mutex, owner) = (self.mutex, self.owner);
+        mem::forget(self);
+        mutex.kill(owner)
+    }
+}
+
+impl Drop for RecoveryGuard {
+    fn drop(&mut self) {
+        cold_path();
+        warn!(target: "ntsync", handle=self.mutex.id, kind="mutex"; "An abandoned mutex was neither marked consistent nor abandoned, abandoning it again");
+        if let Err(error) = self.mutex.kill(self.owner) {
+            warn!(target: "ntsync", handle=self.mutex.id, kind="mutex"; "Failed to abandon the mutex: {error}");
+        }
+    }
+}
+
+#[derive(Debug)]
+#[must_use = "the mutexes are unlocked when the guard is dropped"]
+/// Holds the mutexes locked by [lock_ordered](NtSync::lock_ordered) and unlocks them in the reverse order when it is dropped.
+pub struct MultiGuard {
+    guards: Vec<MutexGuard>,
+}
+
+impl MultiGuard {
+    /// Returns the locked mutexes in the order they were locked.
+    pub fn mutexes(&self) -> Vec<Mutex> {
+        self.guards.iter().map(MutexGuard::mutex).collect()
+    }
+}
+
+impl Drop for MultiGuard {
+    fn drop(&mut self) {
+        while let Some(guard) = self.guards.pop() {
+            drop(guard);
         }
     }
 }
 
 impl NtSync {
+    /// Locks the mutexes one after another in the order of their file descriptors and returns an guard that unlocks all of them.
+    ///
+    /// Every caller locks the same mutexes in the same order, independent of the order of `mutexes`, so two callers can not deadlock on each other.
+    /// Unlike an [wait_all](NtSync::wait_all) the mutexes are not locked together, so this also works for code that locks more mutexes over time.
+    /// Duplicates are locked once. The timeout is one deadline for all mutexes and measured with the realtime clock.
+    /// If an lock fails, the mutexes that were already locked are unlocked before the error is returned.
+    /// An abandoned mutex returns [Error::OwnerDead] and is [abandoned](RecoveryGuard::abandon) again, so the next owner also learns that its state can be inconsistent.
+    pub fn lock_ordered(&self, mutexes: &[Mutex], owner: OwnerId, timeout: Option<SystemTime>) -> Result<MultiGuard> {
+        let mut ordered = mutexes.to_vec();
+        ordered.sort_unstable();
+        ordered.dedup();
+        let mut locked = MultiGuard {
+            guards: Vec::with_capacity(ordered.len()),
+        };
+        for mutex in ordered {
+            match self.lock_mutex_recoverable(mutex, owner, timeout) {
+                Ok(Ok(guard)) => locked.guards.push(guard),
+                Ok(Err(recovery)) => {
+                    cold_path();
+                    debug!(target: "ntsync", handle=mutex.id, kind="mutex"; "The mutex was abandoned, abandoning it again and unlocking {} mutexes", locked.guards.len());
+                    recovery.abandon()?;
+                    return Err(Error::OwnerDead);
+                },
+                Err(error) => {
+                    cold_path();
+                    debug!(target: "ntsync", handle=mutex.id, kind="mutex"; "Failed to lock the mutex in order, unlocking {} mutexes", locked.guards.len());
+                    return Err(error);
+                },
+            }
+        }
+        Ok(locked)
+    }
+
+    /// Locks the mutex for `owner` and returns an guard that unlocks it when it is dropped. The timeout is measured with the realtime clock.
+    ///
+    /// If the mutex was abandoned [Error::OwnerDead] is returned, but the kernel still locked it for `owner`.
+    /// [lock_mutex_recoverable](NtSync::lock_mutex_recoverable) returns an guard for that case.
+    pub fn lock_mutex(&self, mutex: Mutex, owner: OwnerId, timeout: Option<SystemTime>) -> Result<MutexGuard> {
+        self.wait_any(HashSet::from([mutex.into()]), timeout, Some(owner), NtSyncFlags::WaitRealtime, None)?;
+        Ok(MutexGuard {
+            mutex,
+            owner,
+        })
+    }
+
+    /// Locks the mutex like [lock_mutex](NtSync::lock_mutex), but returns an [RecoveryGuard] in the inner [Result](result::Result) if the mutex was abandoned.
+    pub fn lock_mutex_recoverable(&self, mutex: Mutex, owner: OwnerId, timeout: Option<SystemTime>) -> Result<result::Result<MutexGuard, RecoveryGuard>> {
+        match self.wait_any(HashSet::from([mutex.into()]), timeout, Some(owner), NtSyncFlags::WaitRealtime, None) {
+            Ok(_) => {
+                Ok(Ok(MutexGuard {
+                    mutex,
+                    owner,
+                }))
+            },
+            Err(error) if matches!(error.kind(), Error::OwnerDead) => {
+                debug!(target: "ntsync", handle=mutex.id, kind="mutex"; "Locked an abandoned mutex for {owner}");
+                Ok(Err(RecoveryGuard {
+                    mutex,
+                    owner,
+                }))
+            },
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Wraps an mutex that was received as file descriptor, for example with `SCM_RIGHTS` from another process.
+    ///
+    /// Like [adopt_event](NtSync::adopt_event) the file descriptor is probed and [Error::WrongObjectType] is returned if it is not an mutex.
+    /// An abandoned mutex is adopted as well.
+    pub fn adopt_mutex(&self, fd: OwnedFd) -> Result<Mutex> {
+        let mutex: Mutex = adopt(fd, "mutex")?;
+        #[cfg(metrics)]
+        crate::metrics::created(self.inner.handle.as_raw_fd(), mutex.into());
+        Ok(mutex)
+    }
+
     /// Creates an unlocked, unowned Mutex.
     pub fn new_mutex(&self) -> Result<Mutex> {
-        let args = MutexStatus::default();
-        match unsafe { ntsync_create_mutex(self.inner.handle.as_raw_fd(), raw!(const args: MutexStatus)) } {
+        self.create_mutex(MutexArgs::default())
+    }
+
+    /// Creates an Mutex that is already locked `depth` times by `owner`.
+    ///
+    /// The kernel rejects an zero owner with an nonzero depth and the other way around with [Error::InvalidValue].
+    pub fn new_mutex_owned(&self, owner: OwnerId, depth: u32) -> Result<Mutex> {
+        self.create_mutex(MutexArgs {
+            owner,
+            count: depth,
+        })
+    }
+
+    fn create_mutex(&self, args: MutexArgs) -> Result<Mutex> {
+        let result = self.create_limited(|| unsafe { ntsync_create_mutex(self.inner.handle.as_raw_fd(), raw!(const args: MutexArgs)) })?;
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::CreateMutex {
+                    owner: args.owner.0,
+                    count: args.count,
+                },
+                recorder.created(&result, |id| {
+                    EventSources::Mutex(Mutex {
+                        id,
+                    })
+                }),
+            )
+        });
+        match result {
             Ok(fd) => {
+                #[cfg(metrics)]
+                crate::metrics::created(
+                    self.inner.handle.as_raw_fd(),
+                    EventSources::Mutex(Mutex {
+                        id: fd,
+                    }),
+                );
                 Ok(Mutex {
                     id: fd,
                 })
             },
             Err(errno) => {
                 cold_path();
-                trace!(target: "ntsync", handle=self.inner.handle.as_raw_fd(), returncode=errno as i32 ;"Failed to create Mutex");
-                match errno {
-                    Errno::EBADF => Err(Error::AlreadyClosed),
-                    other => {
-                        cold_path();
-                        Err(Error::Unknown(other as i32))
-                    },
-                }
+                trace!(target: "ntsync", handle=self.inner.handle.as_raw_fd(), kind="mutex", returncode=errno as i32 ;"Failed to create Mutex");
+                Err(create_error(errno))
             },
         }
     }
+
+    /// Locks the mutex for `owner` once more. The timeout is measured with the realtime clock.
+    ///
+    /// If the owner already holds the mutex at [max_depth](Mutex::max_depth) it returns [Error::DepthExceeded], where an plain wait would block until the timeout.
+    /// The depth is read before the wait, so this only protects against the own recursion of the owner.
+    pub fn lock_recursive(&self, mutex: Mutex, owner: OwnerId, timeout: Option<SystemTime>) -> Result<()> {
+        let status = match mutex.read() {
+            Ok(status) => Some(status),
+            Err(Error::OwnerDead) => None,
+            Err(error) => return Err(error),
+        };
+        if let Some(status) = status &&
+            status.owner() == Some(owner) &&
+            status.recursion_count() >= Mutex::max_depth()
+        {
+            cold_path();
+            debug!(target: "ntsync", handle=mutex.id, kind="mutex"; "The owner {owner} already holds the mutex at the maximum depth");
+            return Err(Error::DepthExceeded);
+        }
+        self.wait_any(HashSet::from([mutex.into()]), timeout, Some(owner), NtSyncFlags::WaitRealtime, None)?;
+        Ok(())
+    }
 }
 
 impl Sealed for Mutex {}
 
 impl NTSyncObjects for Mutex {
-    type Status = MutexStatus;
+    type Status = MutexSnapshot;
 
     /// deletes the Mutex from the program.
     /// All instances of this Mutex are now invalid
     fn delete(self) -> Result<()> {
-        if unsafe { libc::close(self.id) } == -1 {
-            cold_path();
-            return match Errno::last() {
-                Errno::EBADF => {
-                    trace!(target: "ntsync", handle=self.id; "tried to double close an Mutex");
-                    Err(Error::AlreadyClosed)
+        #[cfg(metrics)]
+        crate::metrics::deleted(self.id);
+        crate::limit::deleted(self.id);
+        let closed = Errno::result(unsafe { libc::close(self.id) });
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::Delete {
+                    object: recorder.object(self.into()),
                 },
-                Errno::EINTR => {
-                    trace!(target: "ntsync", handle=self.id; "While closing the Mutex an interrupt occured");
-                    Err(Error::Interrupt)
-                },
-                Errno::EIO => {
-                    trace!(target: "ntsync", handle=self.id; "While closing the Mutex an IOError occured");
-                    Err(Error::IOError(io::Error::from_raw_os_error(Errno::EIO as i32)))
-                },
-                errno => {
-                    cold_path();
-                    trace!(target: "ntsync", handle=self.id; "Unexpected error while closing the Mutex: {errno}");
-                    Err(Error::Unknown(errno as i32))
-                },
-            };
-        }
-        Ok(())
+                crate::record::Outcome::of(&closed, || crate::record::Outcome::Done),
+            )
+        });
+        crate::close_result(closed, self.id, "mutex")
     }
 
     #[allow(unused)]
     /// reads the current status of the Mutex.
-    fn read(&self) -> Result<MutexStatus> {
-        let mut args = MutexStatus::default();
-        match unsafe { ntsync_mutex_read(self.id, raw!(mut args: MutexStatus)) } {
-            Ok(_) => Ok(args),
+    fn read(&self) -> Result<MutexSnapshot> {
+        let mut args = MutexArgs::default();
+        let result = unsafe { ntsync_mutex_read(self.id, raw!(mut args: MutexArgs)) };
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::ReadMutex {
+                    object: recorder.object((*self).into()),
+                },
+                crate::record::Outcome::of(&result, || crate::record::Outcome::Status(args.owner.0, args.count)),
+            )
+        });
+        match result {
+            Ok(_) => Ok(args.into()),
             Err(errno) => {
                 cold_path();
                 match errno {
@@ -201,10 +586,10 @@ impl NTSyncObjects for Mutex {
 }
 
 //#define NTSYNC_IOC_CREATE_MUTEX         _IOW ('N', 0x84, struct ntsync_mutex_args)
-ioctl_write_ptr!(ntsync_create_mutex, NTSYNC_MAGIC, 0x84, MutexStatus);
+ioctl_write_ptr!(ntsync_create_mutex, abi::MAGIC, abi::CREATE_MUTEX, MutexArgs);
 //#define NTSYNC_IOC_MUTEX_UNLOCK         _IOWR('N', 0x85, struct ntsync_mutex_args)
-ioctl_readwrite!(ntsync_mutex_unlock, NTSYNC_MAGIC, 0x85, MutexStatus);
+ioctl_readwrite!(ntsync_mutex_unlock, abi::MAGIC, abi::MUTEX_UNLOCK, MutexArgs);
 //#define NTSYNC_IOC_MUTEX_KILL           _IOW ('N', 0x86, __u32)
-ioctl_write_ptr!(ntsync_mutex_kill, NTSYNC_MAGIC, 0x86, u32);
+ioctl_write_ptr!(ntsync_mutex_kill, abi::MAGIC, abi::MUTEX_KILL, u32);
 //#define NTSYNC_IOC_MUTEX_READ           _IOR ('N', 0x8c, struct ntsync_mutex_args)
-ioctl_read!(ntsync_mutex_read, NTSYNC_MAGIC, 0x8C, MutexStatus);
+ioctl_read!(ntsync_mutex_read, abi::MAGIC, abi::MUTEX_READ, MutexArgs);
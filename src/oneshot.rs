@@ -0,0 +1,56 @@
+use std::time::SystemTime;
+
+use crate::{
+    Event,
+    NTSyncObjects as _,
+    NtSync,
+    Result,
+    event::wait_event_bool,
+};
+
+#[derive(Debug)]
+/// A [OneShot] is an latch that is fired once and stays fired forever.
+///
+/// It wraps an manual reset [Event] that is never reset, so after [fire](OneShot::fire) all current and future waiters pass.
+/// This is useful for flags like "initialization complete".
+pub struct OneShot {
+    instance: NtSync,
+    event: Event,
+}
+
+impl OneShot {
+    /// Fires the latch. Calling it again has no effect.
+    ///
+    /// Returns true if the latch was already fired.
+    pub fn fire(&self) -> Result<bool> {
+        self.event.signal()
+    }
+
+    /// Returns true if the latch was fired.
+    pub fn is_fired(&self) -> Result<bool> {
+        Ok(self.event.status()?.signaled())
+    }
+
+    /// Waits until the latch is fired, false means the realtime timeout was reached first.
+    pub fn wait(&self, timeout: Option<SystemTime>) -> Result<bool> {
+        wait_event_bool(&self.instance, self.event, timeout)
+    }
+}
+
+impl Drop for OneShot {
+    fn drop(&mut self) {
+        if let Err(error) = self.event.delete() {
+            self.instance.drop_failed(self.event.id, "event of an OneShot", &error);
+        }
+    }
+}
+
+impl NtSync {
+    /// Creates an [OneShot] that is not fired yet.
+    pub fn new_one_shot(&self) -> Result<OneShot> {
+        Ok(OneShot {
+            instance: self.clone(),
+            event: self.new_event(false, true)?,
+        })
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    sync::Arc,
+    time::SystemTime,
+};
+
+use crate::{
+    Event,
+    NTSyncObjects as _,
+    NtSync,
+    Result,
+    event::wait_event_bool,
+};
+
+#[derive(Debug)]
+struct ParkerInner {
+    instance: NtSync,
+    event: Event,
+}
+
+impl Drop for ParkerInner {
+    fn drop(&mut self) {
+        if let Err(error) = self.event.delete() {
+            self.instance.drop_failed(self.event.id, "event of an Parker", &error);
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Blocks the current thread until it is unparked, like [std::thread::park], but backed by an automatic reset [Event].
+///
+/// An [unpark](Unparker::unpark) before the [park](Parker::park) is not lost, the event stays signaled until the next park consumes it.
+/// Multiple unparks before an park are combined into one, like with [std::thread::park].
+/// The event is deleted when the [Parker] and all of its [Unparker]s are dropped.
+pub struct Parker {
+    inner: Arc<ParkerInner>,
+}
+
+#[derive(Debug, Clone)]
+/// Wakes up the [Parker] it was created from.
+pub struct Unparker {
+    inner: Arc<ParkerInner>,
+}
+
+impl Parker {
+    /// Blocks until the parker is unparked and returns false if it timed out instead, measured with the realtime clock.
+    pub fn park(&self, timeout: Option<SystemTime>) -> Result<bool> {
+        wait_event_bool(&self.inner.instance, self.inner.event, timeout)
+    }
+
+    /// Returns an [Unparker] for this parker.
+    pub fn unparker(&self) -> Unparker {
+        Unparker {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl Unparker {
+    /// Wakes up the parker or, if it is not parked, lets its next [park](Parker::park) return immediately.
+    pub fn unpark(&self) -> Result<()> {
+        self.inner.event.signal()?;
+        Ok(())
+    }
+}
+
+impl NtSync {
+    /// Creates an [Parker] that is not unparked yet.
+    pub fn new_parker(&self) -> Result<Parker> {
+        Ok(Parker {
+            inner: Arc::new(ParkerInner {
+                instance: self.clone(),
+                event: self.new_event(false, false)?,
+            }),
+        })
+    }
+}
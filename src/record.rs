@@ -0,0 +1,443 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        Mutex as StdMutex,
+        MutexGuard as StdMutexGuard,
+        PoisonError,
+    },
+    time::Duration,
+};
+
+use log::*;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+#[cfg(semaphore)]
+use crate::semaphore::SemaphoreArgs;
+use crate::{
+    Error,
+    Event,
+    EventSources,
+    Fd,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    OwnerId,
+    Result,
+    cold_path,
+};
+
+/// The recording that is active, [None] if nothing is recorded.
+static RECORDING: StdMutex<Option<Recorder>> = StdMutex::new(None);
+
+fn recording() -> StdMutexGuard<'static, Option<Recorder>> {
+    RECORDING.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An ioctl of the device, with the objects replaced by their index in the [Trace].
+///
+/// The objects are numbered in the order the trace first saw them, so they can be mapped to the objects of an other run.
+pub enum Operation {
+    /// Creates an event.
+    CreateEvent {
+        /// If the event starts signaled.
+        signaled: bool,
+        /// If the event is reset manually.
+        manual: bool,
+    },
+    /// Signals an event.
+    SetEvent {
+        /// The index of the event.
+        object: usize,
+    },
+    /// Resets an event.
+    ResetEvent {
+        /// The index of the event.
+        object: usize,
+    },
+    /// Pulses an event.
+    PulseEvent {
+        /// The index of the event.
+        object: usize,
+    },
+    /// Reads the status of an event.
+    ReadEvent {
+        /// The index of the event.
+        object: usize,
+    },
+    #[cfg(semaphore)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "semaphore")))]
+    /// Creates an semaphore.
+    CreateSemaphore {
+        /// The count the semaphore starts with.
+        count: u32,
+        /// The maximum of the semaphore.
+        max: u32,
+    },
+    #[cfg(semaphore)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "semaphore")))]
+    /// Releases permits of an semaphore.
+    ReleaseSemaphore {
+        /// The index of the semaphore.
+        object: usize,
+        /// How many permits were released.
+        amount: u32,
+    },
+    #[cfg(semaphore)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "semaphore")))]
+    /// Reads the status of an semaphore.
+    ReadSemaphore {
+        /// The index of the semaphore.
+        object: usize,
+    },
+    #[cfg(mutex)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
+    /// Creates an mutex.
+    CreateMutex {
+        /// The owner the mutex starts with, 0 for none.
+        owner: u32,
+        /// How often the owner holds the mutex.
+        count: u32,
+    },
+    #[cfg(mutex)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
+    /// Unlocks an mutex.
+    UnlockMutex {
+        /// The index of the mutex.
+        object: usize,
+        /// The owner that unlocked it.
+        owner: u32,
+    },
+    #[cfg(mutex)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
+    /// Kills the owner of an mutex.
+    KillMutex {
+        /// The index of the mutex.
+        object: usize,
+        /// The owner that was killed.
+        owner: u32,
+    },
+    #[cfg(mutex)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
+    /// Reads the status of an mutex.
+    ReadMutex {
+        /// The index of the mutex.
+        object: usize,
+    },
+    /// Waits until one of the objects is ready.
+    WaitAny {
+        /// The indices of the objects, in the order they were passed to the kernel.
+        objects: Vec<usize>,
+        /// The time that was left until the deadline when the wait started, [None] without an timeout.
+        timeout: Option<Duration>,
+        /// The owner of the wait, 0 for none.
+        owner: u32,
+        /// The bits of the [NtSyncFlags].
+        flags: u32,
+        /// The index of the alert.
+        alert: Option<usize>,
+    },
+    /// Waits until all of the objects are ready.
+    WaitAll {
+        /// The indices of the objects, in the order they were passed to the kernel.
+        objects: Vec<usize>,
+        /// The time that was left until the deadline when the wait started, [None] without an timeout.
+        timeout: Option<Duration>,
+        /// The owner of the wait, 0 for none.
+        owner: u32,
+        /// The bits of the [NtSyncFlags].
+        flags: u32,
+        /// The index of the alert.
+        alert: Option<usize>,
+    },
+    /// Closes an object.
+    Delete {
+        /// The index of the object.
+        object: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// What the kernel returned for an [Operation].
+pub enum Outcome {
+    /// An object was created, with its index in the trace.
+    Created(usize),
+    /// The operation succeeded without returning anything.
+    Done,
+    /// The previous state of an event or the previous count of an semaphore.
+    Value(u32),
+    /// The two fields of the status in the order of the kernel struct:
+    /// `manual, signaled` for events, `count, max` for semaphores and `owner, count` for mutexes.
+    Status(u32, u32),
+    /// An wait for any object acquired the object with this index.
+    Signaled(usize),
+    /// The alert stopped the wait.
+    Alerted,
+    /// The kernel returned this errno.
+    Failed(i32),
+}
+
+impl Outcome {
+    /// Returns the outcome of an successful ioctl with `outcome`, otherwise the errno.
+    pub(crate) fn of<T>(result: &crate::errno::Result<T>, outcome: impl FnOnce() -> Outcome) -> Outcome {
+        match result {
+            Ok(_) => outcome(),
+            Err(errno) => Outcome::Failed(*errno as i32),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An recorded operation with what the kernel returned.
+pub struct TraceEntry {
+    /// The ioctl.
+    pub operation: Operation,
+    /// What the kernel returned.
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+/// An sequence of ioctls recorded with [start_recording](NtSync::start_recording), that can be stored with serde and replayed with [replay](NtSync::replay).
+pub struct Trace {
+    /// The operations in the order they were issued.
+    pub entries: Vec<TraceEntry>,
+}
+
+#[derive(Debug, Default)]
+/// Collects the entries and numbers the objects of an recording.
+pub(crate) struct Recorder {
+    objects: Vec<EventSources>,
+    entries: Vec<TraceEntry>,
+}
+
+impl Recorder {
+    /// Returns the index of `source`. An object that was not created during the recording gets an new index.
+    ///
+    /// File descriptors are reused after an close, so the newest object with the file descriptor is used.
+    pub(crate) fn object(&mut self, source: EventSources) -> usize {
+        match self.objects.iter().rposition(|known| *known == source) {
+            Some(index) => index,
+            None => {
+                self.objects.push(source);
+                self.objects.len() - 1
+            },
+        }
+    }
+
+    /// Returns the outcome of an create ioctl and numbers the new object.
+    pub(crate) fn created(&mut self, result: &crate::errno::Result<Fd>, source: impl FnOnce(Fd) -> EventSources) -> Outcome {
+        match result {
+            Ok(fd) => {
+                self.objects.push(source(*fd));
+                Outcome::Created(self.objects.len() - 1)
+            },
+            Err(errno) => Outcome::Failed(*errno as i32),
+        }
+    }
+}
+
+/// Records an ioctl if an recording is active. `entry` is only called then, so the hot path only pays for the lock.
+pub(crate) fn record(entry: impl FnOnce(&mut Recorder) -> (Operation, Outcome)) {
+    if let Some(recorder) = recording().as_mut() {
+        let (operation, outcome) = entry(recorder);
+        trace!(target: "ntsync", "Recorded {operation:?} with {outcome:?}");
+        recorder.entries.push(TraceEntry {
+            operation,
+            outcome,
+        });
+    }
+}
+
+impl NtSync {
+    /// Starts to record every ioctl on the objects of the device, replacing an recording that is already active.
+    ///
+    /// The recording is global for the process, so it also contains the ioctls of other instances and threads.
+    /// Objects that were created before the recording started get an index when they are first used, but can not be replayed.
+    pub fn start_recording(&self) {
+        *recording() = Some(Recorder::default());
+    }
+
+    /// Stops the recording and returns the trace, [None] if nothing was recorded.
+    pub fn stop_recording(&self) -> Option<Trace> {
+        recording().take().map(|recorder| {
+            Trace {
+                entries: recorder.entries,
+            }
+        })
+    }
+
+    /// Issues the operations of `trace` again on this instance and returns the trace with the new outcomes.
+    ///
+    /// An regression test can compare the result with the recorded trace. The operations are issued without looking at the outcomes,
+    /// so an failed create makes the later operations on its object fail with [Error::InvalidValue].
+    /// Operations that are issued inside of an other one, like the read after an failed unlock, are part of the trace and replayed on their own.
+    /// An active recording is paused during the replay.
+    pub fn replay(&self, trace: &Trace) -> Result<Trace> {
+        let previous = recording().replace(Recorder::default());
+        let replayed = self.replay_entries(trace);
+        *recording() = previous;
+        replayed
+    }
+
+    fn replay_entries(&self, trace: &Trace) -> Result<Trace> {
+        let mut entries = Vec::with_capacity(trace.entries.len());
+        for entry in &trace.entries {
+            let start = recording().as_ref().map_or(0, |recorder| recorder.entries.len());
+            self.issue(&entry.operation)?;
+            let Some(outcome) = recording().as_ref().and_then(|recorder| recorder.entries.get(start)).map(|issued| issued.outcome.clone()) else {
+                cold_path();
+                debug!(target: "ntsync", "The operation {:?} did not reach the kernel during the replay", entry.operation);
+                return Err(Error::InvalidValue);
+            };
+            entries.push(TraceEntry {
+                operation: entry.operation.clone(),
+                outcome,
+            });
+        }
+        Ok(Trace {
+            entries,
+        })
+    }
+
+    /// Issues one operation. The errors of the operation are part of the recorded outcome, only unknown objects are an error.
+    fn issue(&self, operation: &Operation) -> Result<()> {
+        match *operation {
+            Operation::CreateEvent {
+                signaled,
+                manual,
+            } => drop(self.new_event(signaled, manual)),
+            Operation::SetEvent {
+                object,
+            } => drop(replayed_event(object)?.signal()),
+            Operation::ResetEvent {
+                object,
+            } => drop(replayed_event(object)?.reset()),
+            Operation::PulseEvent {
+                object,
+            } => drop(replayed_event(object)?.pulse()),
+            Operation::ReadEvent {
+                object,
+            } => drop(replayed_event(object)?.status()),
+            #[cfg(semaphore)]
+            Operation::CreateSemaphore {
+                count,
+                max,
+            } => drop(self.create_semaphore(SemaphoreArgs::with_count(count, max))),
+            #[cfg(semaphore)]
+            Operation::ReleaseSemaphore {
+                object,
+                amount,
+            } => {
+                let EventSources::Semaphore(semaphore) = replayed(object)? else {
+                    return Err(wrong_kind(object));
+                };
+                drop(semaphore.release(amount));
+            },
+            #[cfg(semaphore)]
+            Operation::ReadSemaphore {
+                object,
+            } => {
+                let EventSources::Semaphore(semaphore) = replayed(object)? else {
+                    return Err(wrong_kind(object));
+                };
+                drop(semaphore.read());
+            },
+            #[cfg(mutex)]
+            Operation::CreateMutex {
+                owner,
+                count,
+            } => drop(self.new_mutex_owned(OwnerId(owner), count)),
+            #[cfg(mutex)]
+            Operation::UnlockMutex {
+                object,
+                owner,
+            } => drop(replayed_mutex(object)?.unlock(OwnerId(owner))),
+            #[cfg(mutex)]
+            Operation::KillMutex {
+                object,
+                owner,
+            } => drop(replayed_mutex(object)?.kill(OwnerId(owner))),
+            #[cfg(mutex)]
+            Operation::ReadMutex {
+                object,
+            } => drop(replayed_mutex(object)?.read()),
+            Operation::WaitAny {
+                ref objects,
+                timeout,
+                owner,
+                flags,
+                alert,
+            } => {
+                let (sources, owner, alert) = replayed_wait(objects, owner, alert)?;
+                drop(self.wait_any_with(sources, timeout, owner, NtSyncFlags::from_bits_retain(flags), alert));
+            },
+            Operation::WaitAll {
+                ref objects,
+                timeout,
+                owner,
+                flags,
+                alert,
+            } => {
+                let (sources, owner, alert) = replayed_wait(objects, owner, alert)?;
+                drop(self.wait_all_with(sources, timeout, owner, NtSyncFlags::from_bits_retain(flags), alert));
+            },
+            Operation::Delete {
+                object,
+            } => {
+                drop(match replayed(object)? {
+                    EventSources::Event(event) => event.delete(),
+                    #[cfg(semaphore)]
+                    EventSources::Semaphore(semaphore) => semaphore.delete(),
+                    #[cfg(mutex)]
+                    EventSources::Mutex(mutex) => mutex.delete(),
+                })
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Returns the object with `index` in the replay.
+fn replayed(index: usize) -> Result<EventSources> {
+    match recording().as_ref().and_then(|recorder| recorder.objects.get(index)) {
+        Some(source) => Ok(*source),
+        None => {
+            cold_path();
+            debug!(target: "ntsync", "The object {index} of the trace was not created during the replay");
+            Err(Error::InvalidValue)
+        },
+    }
+}
+
+fn wrong_kind(index: usize) -> Error {
+    cold_path();
+    debug!(target: "ntsync", "The object {index} of the trace has another type during the replay");
+    Error::InvalidValue
+}
+
+fn replayed_event(index: usize) -> Result<Event> {
+    match replayed(index)? {
+        EventSources::Event(event) => Ok(event),
+        #[allow(unreachable_patterns)]
+        _ => Err(wrong_kind(index)),
+    }
+}
+
+#[cfg(mutex)]
+fn replayed_mutex(index: usize) -> Result<crate::Mutex> {
+    match replayed(index)? {
+        EventSources::Mutex(mutex) => Ok(mutex),
+        _ => Err(wrong_kind(index)),
+    }
+}
+
+type ReplayedWait = (HashSet<EventSources>, Option<OwnerId>, Option<Event>);
+
+fn replayed_wait(objects: &[usize], owner: u32, alert: Option<usize>) -> Result<ReplayedWait> {
+    let sources = objects.iter().map(|object| replayed(*object)).collect::<Result<HashSet<_>>>()?;
+    let alert = alert.map(replayed_event).transpose()?;
+    Ok((sources, (owner != 0).then_some(OwnerId(owner)), alert))
+}
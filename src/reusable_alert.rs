@@ -0,0 +1,143 @@
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::SystemTime,
+};
+
+use log::*;
+
+use crate::{
+    Event,
+    EventSources,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    OwnerId,
+    Result,
+    WaitAllStatus,
+    WaitAnyStatus,
+    cold_path,
+};
+
+#[derive(Debug)]
+struct ReusableAlertInner {
+    instance: NtSync,
+    event: Event,
+}
+
+impl Drop for ReusableAlertInner {
+    fn drop(&mut self) {
+        if let Err(error) = self.event.delete() {
+            self.instance.drop_failed(self.event.id, "event of an ReusableAlert", &error);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// An manual reset alert that is reset after each wait it stopped, so it can be reused in an loop.
+///
+/// An plain manual reset alert stays signaled after it stopped an wait, so every following wait reports alerted immediately until it is reset.
+/// The waits of this type return an [AlertReset] alongside the status, which resets the alert when it is dropped if it stopped the wait.
+/// An [fire](ReusableAlert::fire) during an wait that succeeded through its objects is not lost, the next wait reports it.
+/// The clones share the event, which is deleted when the last clone is dropped.
+pub struct ReusableAlert {
+    inner: Arc<ReusableAlertInner>,
+}
+
+impl ReusableAlert {
+    /// Signals the alert, which stops the current or the next wait with it. Returns true if it was already signaled.
+    pub fn fire(&self) -> Result<bool> {
+        self.inner.event.signal()
+    }
+
+    /// Returns true if the alert was fired and not reset yet.
+    pub fn is_fired(&self) -> Result<bool> {
+        Ok(self.inner.event.status()?.signaled())
+    }
+
+    /// Returns the underlying [Event]. Passing it as plain alert to an wait skips the automatic reset.
+    ///
+    /// The returned [Event] does not keep the file descriptor open, so it must not be used after the last clone of this [ReusableAlert] was dropped.
+    pub fn event(&self) -> Event {
+        self.inner.event
+    }
+}
+
+#[derive(Debug)]
+#[must_use = "the alert is reset when the guard is dropped"]
+/// Resets an [ReusableAlert] when it is dropped, if the alert stopped the wait that returned this guard.
+pub struct AlertReset<'a> {
+    alert: &'a ReusableAlert,
+    alerted: bool,
+}
+
+impl AlertReset<'_> {
+    /// Returns true if the alert stopped the wait and is reset by this guard.
+    pub fn alerted(&self) -> bool {
+        self.alerted
+    }
+}
+
+impl Drop for AlertReset<'_> {
+    fn drop(&mut self) {
+        if !self.alerted {
+            return;
+        }
+        if let Err(error) = self.alert.inner.event.reset() {
+            cold_path();
+            warn!(target: "ntsync", handle=self.alert.inner.event.id, kind="event"; "Failed to reset the ReusableAlert: {error}");
+        }
+    }
+}
+
+impl NtSync {
+    /// Creates an [ReusableAlert] that is not fired.
+    pub fn new_reusable_alert(&self) -> Result<ReusableAlert> {
+        Ok(ReusableAlert {
+            inner: Arc::new(ReusableAlertInner {
+                instance: self.clone(),
+                event: self.new_event(false, true)?,
+            }),
+        })
+    }
+
+    /// Works like [wait_all](NtSync::wait_all) with the [ReusableAlert] as alert, which is reset when the returned [AlertReset] is dropped.
+    pub fn wait_all_reusable<'a>(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: Option<SystemTime>,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: &'a ReusableAlert,
+    ) -> Result<(WaitAllStatus, AlertReset<'a>)> {
+        let status = self.wait_all(sources, timeout, owner, flags, Some(alert.event()))?;
+        let alerted = status.alerted;
+        Ok((
+            status,
+            AlertReset {
+                alert,
+                alerted,
+            },
+        ))
+    }
+
+    /// Works like [wait_any](NtSync::wait_any) with the [ReusableAlert] as alert, which is reset when the returned [AlertReset] is dropped.
+    pub fn wait_any_reusable<'a>(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: Option<SystemTime>,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: &'a ReusableAlert,
+    ) -> Result<(WaitAnyStatus, AlertReset<'a>)> {
+        let status = self.wait_any(sources, timeout, owner, flags, Some(alert.event()))?;
+        let alerted = status.alerted;
+        Ok((
+            status,
+            AlertReset {
+                alert,
+                alerted,
+            },
+        ))
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    collections::HashSet,
+    time::UNIX_EPOCH,
+};
+
+use log::*;
+
+#[cfg(mutex)]
+use crate::OwnerId;
+use crate::{
+    Error,
+    NTSyncObjects,
+    NtSync,
+    NtSyncFlags,
+    Result,
+    cold_path,
+};
+
+#[derive(Debug)]
+/// The result of [self_test](NtSync::self_test) for each primitive of this build.
+pub struct SelfTestReport {
+    /// Create, signal, wait, pulse, reset, read and delete of an event.
+    pub event: Result<()>,
+    #[cfg(semaphore)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "semaphore")))]
+    /// Create, wait, release, read and delete of an semaphore.
+    pub semaphore: Result<()>,
+    #[cfg(mutex)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
+    /// Create, lock, read, unlock, kill and delete of an mutex.
+    pub mutex: Result<()>,
+}
+
+impl SelfTestReport {
+    /// Returns true if all primitives worked.
+    pub fn is_ok(&self) -> bool {
+        self.failures().is_empty()
+    }
+
+    /// Returns the name and the error of each primitive that failed.
+    pub fn failures(&self) -> Vec<(&'static str, &Error)> {
+        let results = [
+            Some(("event", &self.event)),
+            #[cfg(semaphore)]
+            Some(("semaphore", &self.semaphore)),
+            #[cfg(mutex)]
+            Some(("mutex", &self.mutex)),
+        ];
+        results.into_iter().flatten().filter_map(|(name, result)| result.as_ref().err().map(|error| (name, error))).collect()
+    }
+}
+
+/// Returns [Error::InvalidValue] if the kernel answered differently than the crate expects.
+fn expect(condition: bool, what: &str) -> Result<()> {
+    if condition {
+        return Ok(());
+    }
+    cold_path();
+    warn!(target: "ntsync", "Self test: {what}");
+    Err(Error::InvalidValue)
+}
+
+/// Runs `probe` on the object and deletes it afterwards, also if the probe failed. The error of the probe is returned first.
+fn probe<T: NTSyncObjects + Copy>(object: Result<T>, probe: impl FnOnce(T) -> Result<()>) -> Result<()> {
+    let object = object?;
+    let result = probe(object);
+    let deleted = object.delete();
+    result.and(deleted)
+}
+
+impl NtSync {
+    /// Creates one object of each primitive of this build, runs every ioctl on it once and deletes it again.
+    ///
+    /// This checks at runtime that the kernel understands the ioctls of this crate, for example on an backport of the driver to an older kernel.
+    /// The static checks of the [abi](crate::abi) only cover the numbers. An failure of one primitive does not stop the others,
+    /// and every probe object is deleted, unless the creation or the deletion itself failed.
+    pub fn self_test(&self) -> SelfTestReport {
+        let event = probe(self.new_event(false, true), |event| {
+            expect(!event.signal()?, "the new event was signaled")?;
+            expect(event.status()?.signaled(), "the signal was lost")?;
+            self.wait_any(HashSet::from([event.into()]), Some(UNIX_EPOCH), None, NtSyncFlags::WaitRealtime, None)?;
+            expect(event.reset()?, "the reset did not see the signal")?;
+            event.pulse()?;
+            expect(!event.status()?.signaled(), "the pulse left the event signaled")
+        });
+        #[cfg(semaphore)]
+        let semaphore = probe(self.new_semaphore(2), |semaphore| {
+            self.wait_all(HashSet::from([semaphore.into()]), Some(UNIX_EPOCH), None, NtSyncFlags::WaitRealtime, None)?;
+            expect(semaphore.read()?.count == 1, "the wait did not take an permit")?;
+            expect(semaphore.release(1)? == 1, "the release returned the wrong count")?;
+            expect(semaphore.read()?.count == 2, "the release did not give the permit back")
+        });
+        #[cfg(mutex)]
+        let mutex = probe(self.new_mutex(), |mutex| {
+            let owner = OwnerId::new(1);
+            self.wait_any(HashSet::from([mutex.into()]), Some(UNIX_EPOCH), Some(owner), NtSyncFlags::WaitRealtime, None)?;
+            expect(mutex.read()?.owner() == Some(owner), "the wait did not lock the mutex")?;
+            mutex.unlock(owner)?;
+            self.wait_any(HashSet::from([mutex.into()]), Some(UNIX_EPOCH), Some(owner), NtSyncFlags::WaitRealtime, None)?;
+            mutex.kill(owner)?;
+            expect(mutex.read().is_err_and(|error| error == Error::OwnerDead), "the kill did not abandon the mutex")
+        });
+        let report = SelfTestReport {
+            event,
+            #[cfg(semaphore)]
+            semaphore,
+            #[cfg(mutex)]
+            mutex,
+        };
+        debug!(target: "ntsync", "Self test finished, {} primitives failed", report.failures().len());
+        report
+    }
+}
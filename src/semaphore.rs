@@ -1,54 +1,116 @@
 use std::{
-    io,
-    os::fd::AsRawFd as _,
+    collections::HashSet,
+    mem,
+    os::fd::{
+        AsRawFd,
+        FromRawFd,
+        OwnedFd,
+    },
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
 };
 
 use crate::{
     Error,
     EventSources,
     Fd,
-    NTSYNC_MAGIC,
     NTSyncObjects,
     NtSync,
+    NtSyncFlags,
     Result,
     Sealed,
+    abi,
+    adopt,
     cold_path,
+    create_error,
+    errno::Errno,
+    ioctl::{
+        ioctl_read,
+        ioctl_readwrite,
+        ioctl_write_ptr,
+    },
     raw,
 };
 use derive_new::new;
 use log::*;
-use nix::{
-    errno::Errno,
-    ioctl_read,
-    ioctl_readwrite,
-    ioctl_write_ptr,
-    libc,
-};
 
 
 #[repr(C)]
 #[derive(Debug, new, Default)]
 #[new(visibility = "pub(crate)")]
-/// [SemaphoreStatus] is the Status of the Semaphore at the time the [read](Semaphore::read) method was called.
-pub struct SemaphoreStatus {
+/// The arguments of the semaphore ioctls, `struct ntsync_sem_args` of the kernel.
+pub(crate) struct SemaphoreArgs {
     #[new(value = "max")]
+    count: u32,
+    max: u32,
+}
+
+impl SemaphoreArgs {
+    /// The arguments for an semaphore that does not start at its maximum.
+    #[cfg(any(mutex, record, test))]
+    pub(crate) fn with_count(count: u32, max: u32) -> Self {
+        SemaphoreArgs {
+            count,
+            max,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(serialize, derive(serde::Serialize))]
+/// [SemaphoreSnapshot] is the Status of the Semaphore at the time the [read](Semaphore::read) method was called.
+pub struct SemaphoreSnapshot {
     /// count is the amount that can be allocated.
     ///
+    /// It counts the remaining permits, not the consumed ones, so it is never above the [max](SemaphoreSnapshot::max).
+    /// An [read](Semaphore::read) that receives an higher count from the kernel fails with [Error::InvalidValue], because the kernel interprets the fields differently.
+    ///
     /// it is changed with the [release](Semaphore::release) method and waiting on the Semaphore with [wait_any](NtSync::wait_any) or [wait_all](NtSync::wait_all).
     pub count: u32,
     max: u32,
 }
 
-impl SemaphoreStatus {
+impl SemaphoreSnapshot {
     /// returns the maximum of allocatable resources.
     pub fn max(&self) -> u32 {
         self.max
     }
+
+    /// Returns how many permits can be acquired right now, which is the [count](SemaphoreSnapshot::count) of the kernel.
+    pub fn available(&self) -> u32 {
+        self.count
+    }
+
+    /// Returns how many permits are acquired and not released yet, which is the [max](SemaphoreSnapshot::max) minus the [count](SemaphoreSnapshot::count).
+    pub fn in_use(&self) -> u32 {
+        self.max.saturating_sub(self.count)
+    }
+}
+
+impl TryFrom<SemaphoreArgs> for SemaphoreSnapshot {
+    type Error = Error;
+
+    /// Fails with [Error::InvalidValue] if the count is above the maximum, because then the kernel interprets the fields differently.
+    fn try_from(args: SemaphoreArgs) -> Result<Self> {
+        if args.count > args.max {
+            cold_path();
+            warn!(target: "ntsync", kind="semaphore"; "The kernel reported an count of {} above the maximum of {}, the ABI of the device does not match", args.count, args.max);
+            return Err(Error::InvalidValue);
+        }
+        Ok(SemaphoreSnapshot {
+            count: args.count,
+            max: args.max,
+        })
+    }
 }
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 /// An Semaphore. When the counter reaches 0 Threads will wait until one thread release an specific amount of resources.
 /// <div class="warning">Do not release Resources when the ressources are allocated. This can lead to Reduced Perfomance when they are released.</div>
+///
+/// The ordering is by the file descriptor, so it is only meaningful inside the current process.
 pub struct Semaphore {
     pub(crate) id: Fd,
 }
@@ -61,13 +123,114 @@ impl From<Semaphore> for EventSources {
     }
 }
 
+impl AsRawFd for Semaphore {
+    fn as_raw_fd(&self) -> Fd {
+        self.id
+    }
+}
+
+impl FromRawFd for Semaphore {
+    /// Wraps an file descriptor that was received from another part of the program or another process.
+    ///
+    /// # Safety
+    /// The file descriptor must be an semaphore of the ntsync device, otherwise all operations fail.
+    unsafe fn from_raw_fd(fd: Fd) -> Self {
+        Semaphore {
+            id: fd,
+        }
+    }
+}
+
 
 impl Semaphore {
+    /// Acquires one permit, runs `f` and releases the permit again, also if `f` panics.
+    ///
+    /// The timeout is measured with the realtime clock. If no permit is acquired in time [Error::Timeout] is returned and `f` is not run.
+    pub fn with_permit<R>(&self, instance: &NtSync, timeout: Option<SystemTime>, f: impl FnOnce() -> R) -> Result<R> {
+        let _permit = self.acquire_permit(instance, timeout)?;
+        Ok(f())
+    }
+
+    /// Acquires one permit, runs `f` and keeps the permit only if `f` returns true as first element, otherwise it is released again.
+    ///
+    /// This lets an consumer look at an resource and give it back if it does not want it. If `f` panics the permit is released as well.
+    /// The timeout is measured with the realtime clock. If no permit is acquired in time [Error::Timeout] is returned and `f` is not run.
+    /// An other waiter can take the released permit before this one tries again.
+    pub fn acquire_peek<R>(&self, instance: &NtSync, timeout: Option<SystemTime>, f: impl FnOnce() -> (bool, R)) -> Result<R> {
+        let permit = self.acquire_permit(instance, timeout)?;
+        let (consumed, result) = f();
+        if consumed {
+            mem::forget(permit);
+        }
+        Ok(result)
+    }
+
+    /// Acquires one permit and returns an guard that releases it again. The timeout is measured with the realtime clock.
+    fn acquire_permit(&self, instance: &NtSync, timeout: Option<SystemTime>) -> Result<Permit> {
+        instance.wait_any(HashSet::from([(*self).into()]), timeout, None, NtSyncFlags::WaitRealtime, None)?;
+        Ok(Permit {
+            semaphore: *self,
+        })
+    }
+
+    /// Acquires one permit and returns how many permits are left right after it.
+    ///
+    /// The timeout is measured with the realtime clock. The count is read with an separate ioctl after the wait,
+    /// so concurrent acquirers and releases between the two can already have changed it. It is only an hint for backpressure.
+    /// If the read fails the permit stays acquired and the error is returned.
+    pub fn acquire_and_remaining(&self, instance: &NtSync, timeout: Option<SystemTime>) -> Result<u32> {
+        instance.wait_any(HashSet::from([(*self).into()]), timeout, None, NtSyncFlags::WaitRealtime, None)?;
+        Ok(self.read()?.available())
+    }
+
+    /// Acquires permits with an zero timeout until none are left and returns how many were taken, for example to reset an semaphore between test cases.
+    ///
+    /// Each permit is an own wait, so this is not atomic. Releases that happen while it drains are drained as well,
+    /// and an release right after the last wait leaves the semaphore with permits again.
+    pub fn drain_all(&self, instance: &NtSync) -> Result<u32> {
+        let mut drained = 0;
+        loop {
+            match instance.wait_any(HashSet::from([(*self).into()]), Some(UNIX_EPOCH), None, NtSyncFlags::WaitRealtime, None) {
+                Ok(_) => drained += 1,
+                Err(error) if matches!(error.kind(), Error::Timeout) => break,
+                Err(error) => return Err(error),
+            }
+        }
+        trace!(target: "ntsync", handle=self.id, kind="semaphore"; "Drained {drained} permits");
+        Ok(drained)
+    }
+
+    #[cfg(debug_semaphore)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "debug-semaphore")))]
+    /// Returns how many accounting warnings were logged for this semaphore.
+    ///
+    /// A warning is logged when more permits are released than were acquired, or when the count the kernel reports differs from the tracked one.
+    pub fn accounting_warnings(&self) -> u32 {
+        crate::accounting::warnings(self.id)
+    }
+
     /// After the work is done increment the semaphore with this count, so that `amount` threads are woken up.
     /// If an Error was returned the semaphore is NOT changed.
     /// It returns the previous count on return.
+    ///
+    /// With the `debug-semaphore` feature an warning is logged when more permits are released than were acquired.
     pub fn release(&self, mut amount: u32) -> Result<u32> {
-        match unsafe { ntsync_sem_release(self.id, raw!(mut amount: u32)) } {
+        #[cfg(debug_semaphore)]
+        crate::accounting::released(self.id, amount);
+        #[cfg(record)]
+        let requested = amount;
+        let result = unsafe { ntsync_sem_release(self.id, raw!(mut amount: u32)) };
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::ReleaseSemaphore {
+                    object: recorder.object((*self).into()),
+                    amount: requested,
+                },
+                crate::record::Outcome::of(&result, || crate::record::Outcome::Value(amount)),
+            )
+        });
+        let outcome = match result {
             Ok(_) => Ok(amount),
             Err(errno) => {
                 cold_path();
@@ -80,49 +243,58 @@ impl Semaphore {
                     },
                 }
             },
-        }
+        };
+        outcome.map_err(|error| error.with_context("release"))
     }
 }
 
 impl Sealed for Semaphore {}
 
 impl NTSyncObjects for Semaphore {
-    type Status = SemaphoreStatus;
+    type Status = SemaphoreSnapshot;
 
     /// deletes the event from the program.
     /// All instances of this event are now invalid
     fn delete(self) -> Result<()> {
-        if unsafe { libc::close(self.id) } == -1 {
-            cold_path();
-            return match Errno::last() {
-                Errno::EBADF => {
-                    trace!(target: "ntsync", handle=self.id; "tried to double close an Semaphore");
-                    Err(Error::AlreadyClosed)
-                },
-                Errno::EINTR => {
-                    trace!(target: "ntsync", handle=self.id; "While closing the Semaphore an interrupt occured");
-                    Err(Error::Interrupt)
+        #[cfg(debug_semaphore)]
+        crate::accounting::forget(self.id);
+        #[cfg(metrics)]
+        crate::metrics::deleted(self.id);
+        crate::limit::deleted(self.id);
+        let closed = Errno::result(unsafe { libc::close(self.id) });
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::Delete {
+                    object: recorder.object(self.into()),
                 },
-                Errno::EIO => {
-                    trace!(target: "ntsync", handle=self.id; "While closing the Semaphore an IOError occured");
-                    Err(Error::IOError(io::Error::from_raw_os_error(Errno::EIO as i32)))
-                },
-                errno => {
-                    cold_path();
-                    trace!(target: "ntsync", handle=self.id; "Unexpected error while closing the semaphore: {errno}");
-                    Err(Error::Unknown(errno as i32))
-                },
-            };
-        }
-        Ok(())
+                crate::record::Outcome::of(&closed, || crate::record::Outcome::Done),
+            )
+        });
+        crate::close_result(closed, self.id, "semaphore")
     }
 
     #[allow(unused)]
     /// Queries the kernel about the current status of the semaphore
-    fn read(&self) -> Result<SemaphoreStatus> {
-        let mut args = SemaphoreStatus::default();
-        match unsafe { ntsync_sem_read(self.id, raw!(mut args: SemaphoreStatus)) } {
-            Ok(_) => Ok(args),
+    fn read(&self) -> Result<SemaphoreSnapshot> {
+        let mut args = SemaphoreArgs::default();
+        let result = unsafe { ntsync_sem_read(self.id, raw!(mut args: SemaphoreArgs)) };
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::ReadSemaphore {
+                    object: recorder.object((*self).into()),
+                },
+                crate::record::Outcome::of(&result, || crate::record::Outcome::Status(args.count, args.max)),
+            )
+        });
+        match result {
+            Ok(_) => {
+                let snapshot = SemaphoreSnapshot::try_from(args)?;
+                #[cfg(debug_semaphore)]
+                crate::accounting::compare(self.id, &snapshot);
+                Ok(snapshot)
+            },
             Err(Errno::EBADF) => {
                 cold_path();
                 Err(Error::AlreadyClosed)
@@ -136,30 +308,145 @@ impl NTSyncObjects for Semaphore {
 }
 
 
+#[derive(Debug)]
+#[must_use = "the permit is released when the guard is dropped"]
+/// Holds one permit of an counting semaphore and releases it when it is dropped.
+struct Permit {
+    semaphore: Semaphore,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if let Err(error) = self.semaphore.release(1) {
+            cold_path();
+            warn!(target: "ntsync", handle=self.semaphore.id, kind="semaphore"; "Failed to release the permit: {error}");
+        }
+    }
+}
+
+#[derive(Debug)]
+#[must_use = "the lock is released when the guard is dropped"]
+/// Holds an binary lock from [acquire_binary_lock](NtSync::acquire_binary_lock) and releases it when it is dropped.
+pub struct BinaryLockGuard {
+    semaphore: Semaphore,
+}
+
+impl BinaryLockGuard {
+    /// Returns the semaphore of the lock.
+    pub fn semaphore(&self) -> Semaphore {
+        self.semaphore
+    }
+}
+
+impl Drop for BinaryLockGuard {
+    fn drop(&mut self) {
+        if let Err(error) = self.semaphore.release(1) {
+            cold_path();
+            warn!(target: "ntsync", handle=self.semaphore.id, kind="semaphore"; "Failed to release the binary lock: {error}");
+        }
+    }
+}
+
 impl NtSync {
+    /// Creates an semaphore with an maximum and count of 1, which works as an lock without an owner.
+    ///
+    /// It is acquired by waiting on it, for example with [acquire_binary_lock](NtSync::acquire_binary_lock), and released with [release](Semaphore::release).
+    /// Unlike an [Mutex](crate::Mutex) any thread can release it and it can not be locked recursively.
+    /// Releasing it while it is not acquired fails with [Error::SemaphoreOverflow].
+    pub fn new_binary_lock(&self) -> Result<Semaphore> {
+        self.new_semaphore(1)
+    }
+
+    /// Acquires an binary lock and returns an guard that releases it when it is dropped. The timeout is measured with the realtime clock.
+    pub fn acquire_binary_lock(&self, lock: Semaphore, timeout: Option<SystemTime>) -> Result<BinaryLockGuard> {
+        self.wait_any(HashSet::from([lock.into()]), timeout, None, NtSyncFlags::WaitRealtime, None)?;
+        Ok(BinaryLockGuard {
+            semaphore: lock,
+        })
+    }
+
+    /// Wraps an semaphore that was received as file descriptor, for example with `SCM_RIGHTS` from another process.
+    ///
+    /// Like [adopt_event](NtSync::adopt_event) the file descriptor is probed and [Error::WrongObjectType] is returned if it is not an semaphore.
+    pub fn adopt_semaphore(&self, fd: OwnedFd) -> Result<Semaphore> {
+        let semaphore: Semaphore = adopt(fd, "semaphore")?;
+        #[cfg(debug_semaphore)]
+        crate::accounting::track(semaphore.id, &semaphore.read()?);
+        #[cfg(metrics)]
+        crate::metrics::created(self.inner.handle.as_raw_fd(), semaphore.into());
+        Ok(semaphore)
+    }
+
     /// creates a new Semaphore. it is always initalized with an Maximum between 1 and [u32::MAX] and an count that is the same as the maximum.
     pub fn new_semaphore(&self, maximum: u32) -> Result<Semaphore> {
-        let args = SemaphoreStatus::new(maximum.clamp(1, u32::MAX));
-        match unsafe { ntsync_create_sem(self.inner.handle.as_raw_fd(), raw!(const args: SemaphoreStatus)) } {
+        self.create_semaphore(SemaphoreArgs::new(maximum.clamp(1, u32::MAX)))
+    }
+
+    /// Creates an semaphore with the count and maximum of `args`.
+    pub(crate) fn create_semaphore(&self, args: SemaphoreArgs) -> Result<Semaphore> {
+        let result = self.create_limited(|| unsafe { ntsync_create_sem(self.inner.handle.as_raw_fd(), raw!(const args: SemaphoreArgs)) })?;
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::CreateSemaphore {
+                    count: args.count,
+                    max: args.max,
+                },
+                recorder.created(&result, |id| {
+                    EventSources::Semaphore(Semaphore {
+                        id,
+                    })
+                }),
+            )
+        });
+        match result {
             Ok(fd) => {
+                #[cfg(debug_semaphore)]
+                if let Ok(snapshot) = SemaphoreSnapshot::try_from(args) {
+                    crate::accounting::track(fd, &snapshot);
+                }
+                #[cfg(metrics)]
+                crate::metrics::created(
+                    self.inner.handle.as_raw_fd(),
+                    EventSources::Semaphore(Semaphore {
+                        id: fd,
+                    }),
+                );
                 Ok(Semaphore {
                     id: fd,
                 })
             },
             Err(errno) => {
-                trace!(target: "ntsync",  handle=self.inner.handle.as_raw_fd(), returncode=errno as i32 ;"Failed to create semaphore");
-                match errno {
-                    Errno::EINVAL => Err(Error::InvalidValue),
-                    other => Err(Error::Unknown(other as i32)),
-                }
+                cold_path();
+                trace!(target: "ntsync", handle=self.inner.handle.as_raw_fd(), kind="semaphore", returncode=errno as i32 ;"Failed to create semaphore");
+                Err(create_error(errno))
             },
         }
     }
 }
 
 //#define NTSYNC_IOC_CREATE_SEM           _IOW ('N', 0x80, struct ntsync_sem_args)
-ioctl_write_ptr!(ntsync_create_sem, NTSYNC_MAGIC, 0x80, SemaphoreStatus);
+ioctl_write_ptr!(ntsync_create_sem, abi::MAGIC, abi::CREATE_SEM, SemaphoreArgs);
 //#define NTSYNC_IOC_SEM_READ             _IOR ('N', 0x8b, struct ntsync_sem_args)
-ioctl_read!(ntsync_sem_read, NTSYNC_MAGIC, 0x8B, SemaphoreStatus);
+ioctl_read!(ntsync_sem_read, abi::MAGIC, abi::SEM_READ, SemaphoreArgs);
 //#define NTSYNC_IOC_SEM_RELEASE          _IOWR('N', 0x81, __u32)
-ioctl_readwrite!(ntsync_sem_release, NTSYNC_MAGIC, 0x81, u32);
+ioctl_readwrite!(ntsync_sem_release, abi::MAGIC, abi::SEM_RELEASE, u32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_rejects_count_above_max() {
+        assert_eq!(SemaphoreSnapshot::try_from(SemaphoreArgs::with_count(3, 2)), Err(Error::InvalidValue));
+    }
+
+    #[test]
+    fn snapshot_accepts_count_up_to_max() -> Result<()> {
+        let snapshot = SemaphoreSnapshot::try_from(SemaphoreArgs::with_count(2, 2))?;
+        assert_eq!((snapshot.available(), snapshot.max()), (2, 2));
+        let snapshot = SemaphoreSnapshot::try_from(SemaphoreArgs::with_count(0, 5))?;
+        assert_eq!((snapshot.available(), snapshot.in_use()), (0, 5));
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    sync::Arc,
+    time::SystemTime,
+};
+
+use crate::{
+    Event,
+    EventSnapshot,
+    NTSyncObjects as _,
+    NtSync,
+    Result,
+    event::wait_event_bool,
+};
+
+#[derive(Debug)]
+struct SharedEventInner {
+    instance: NtSync,
+    event: Event,
+}
+
+impl Drop for SharedEventInner {
+    fn drop(&mut self) {
+        if let Err(error) = self.event.delete() {
+            self.instance.drop_failed(self.event.id, "event of an SharedEvent", &error);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// An [Event] that is deleted when the last clone is dropped, so it does not need an manual [delete](crate::NTSyncObjects::delete).
+///
+/// [Event] is [Copy], so it is easy to lose track of the copies and leak the file descriptor or delete it while an copy is still used.
+/// The clones of an [SharedEvent] share the file descriptor and the waits of [wait](SharedEvent::wait) keep it open until they return.
+pub struct SharedEvent {
+    inner: Arc<SharedEventInner>,
+}
+
+impl SharedEvent {
+    /// Signals the event. Returns true if it was already signaled.
+    pub fn signal(&self) -> Result<bool> {
+        self.inner.event.signal()
+    }
+
+    /// Resets the event. Returns true if it was signaled.
+    pub fn reset(&self) -> Result<bool> {
+        self.inner.event.reset()
+    }
+
+    /// Wakes up the threads that are waiting right now and leaves the event unsignaled. Returns true if it was signaled.
+    pub fn pulse(&self) -> Result<bool> {
+        self.inner.event.pulse()
+    }
+
+    /// Returns the Status at the moment of the Query.
+    pub fn status(&self) -> Result<EventSnapshot> {
+        self.inner.event.status()
+    }
+
+    /// Waits for the event with an realtime timeout. Returns if it was signaled before the timeout.
+    pub fn wait(&self, timeout: Option<SystemTime>) -> Result<bool> {
+        wait_event_bool(&self.inner.instance, self.inner.event, timeout)
+    }
+
+    /// Returns the underlying [Event], for example to add it to an wait set.
+    ///
+    /// The returned [Event] does not keep the file descriptor open, so it must not be used after the last clone of this [SharedEvent] was dropped.
+    pub fn event(&self) -> Event {
+        self.inner.event
+    }
+}
+
+impl NtSync {
+    /// Creates an [SharedEvent], see [new_event](NtSync::new_event) for the arguments.
+    pub fn new_shared_event(&self, signaled: bool, manual: bool) -> Result<SharedEvent> {
+        Ok(SharedEvent {
+            inner: Arc::new(SharedEventInner {
+                instance: self.clone(),
+                event: self.new_event(signaled, manual)?,
+            }),
+        })
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{
+    Event,
+    EventSnapshot,
+    EventSources,
+    NTSyncObjects,
+    NtSync,
+    Result,
+    Sealed,
+};
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+/// An manual reset [Event], where the reset mode is part of the type.
+///
+/// A signal stays until it is [reset](ManualEvent::reset), so every wait on it returns while it is signaled and all waiting threads are woken up.
+///
+/// It converts into an [Event] or [EventSources] for wait sets.
+pub struct ManualEvent(Event);
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+/// An automatic reset [Event], where the reset mode is part of the type.
+///
+/// A signal is consumed by the first wait that sees it, so only one waiting thread is woken up per signal.
+/// It has no `reset`, because the kernel resets it on its own:
+///
+/// ```compile_fail
+/// # use ntsync::{Error, NtSync};
+/// # fn main() -> Result<(), Error> {
+/// let instance = NtSync::new()?;
+/// instance.new_auto_event(true)?.reset()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// It converts into an [Event] or [EventSources] for wait sets.
+pub struct AutoEvent(Event);
+
+impl ManualEvent {
+    /// Signals the event and wakes up all threads waiting on it. Returns true if it was already signaled.
+    pub fn signal(&self) -> Result<bool> {
+        self.0.signal()
+    }
+
+    /// Resets the event, so following waits block again. Returns true if it was signaled.
+    pub fn reset(&self) -> Result<bool> {
+        self.0.reset()
+    }
+
+    /// Wakes up all threads that are waiting right now and leaves the event unsignaled. Returns true if it was signaled.
+    pub fn pulse(&self) -> Result<bool> {
+        self.0.pulse()
+    }
+
+    /// Returns the Status at the moment of the Query.
+    pub fn status(&self) -> Result<EventSnapshot> {
+        self.0.status()
+    }
+
+    /// Returns the untyped [Event].
+    pub fn event(&self) -> Event {
+        self.0
+    }
+}
+
+impl AutoEvent {
+    /// Signals the event, which wakes up one waiting thread or stays until the next wait consumes it. Returns true if it was already signaled.
+    pub fn signal(&self) -> Result<bool> {
+        self.0.signal()
+    }
+
+    /// Wakes up one thread that is waiting right now and leaves the event unsignaled. Returns true if it was signaled.
+    pub fn pulse(&self) -> Result<bool> {
+        self.0.pulse()
+    }
+
+    /// Returns the Status at the moment of the Query.
+    pub fn status(&self) -> Result<EventSnapshot> {
+        self.0.status()
+    }
+
+    /// Returns the untyped [Event].
+    pub fn event(&self) -> Event {
+        self.0
+    }
+}
+
+impl From<ManualEvent> for Event {
+    fn from(event: ManualEvent) -> Self {
+        event.0
+    }
+}
+
+impl From<AutoEvent> for Event {
+    fn from(event: AutoEvent) -> Self {
+        event.0
+    }
+}
+
+impl From<ManualEvent> for EventSources {
+    fn from(event: ManualEvent) -> Self {
+        EventSources::Event(event.0)
+    }
+}
+
+impl From<AutoEvent> for EventSources {
+    fn from(event: AutoEvent) -> Self {
+        EventSources::Event(event.0)
+    }
+}
+
+impl Sealed for ManualEvent {}
+
+impl NTSyncObjects for ManualEvent {
+    type Status = EventSnapshot;
+
+    fn delete(self) -> Result<()> {
+        self.0.delete()
+    }
+
+    fn read(&self) -> Result<Self::Status> {
+        self.0.status()
+    }
+}
+
+impl Sealed for AutoEvent {}
+
+impl NTSyncObjects for AutoEvent {
+    type Status = EventSnapshot;
+
+    fn delete(self) -> Result<()> {
+        self.0.delete()
+    }
+
+    fn read(&self) -> Result<Self::Status> {
+        self.0.status()
+    }
+}
+
+impl NtSync {
+    /// Creates an [ManualEvent].
+    pub fn new_manual_event(&self, signaled: bool) -> Result<ManualEvent> {
+        Ok(ManualEvent(self.new_event(signaled, true)?))
+    }
+
+    /// Creates an [AutoEvent].
+    pub fn new_auto_event(&self, signaled: bool) -> Result<AutoEvent> {
+        Ok(AutoEvent(self.new_event(signaled, false)?))
+    }
+}
@@ -1,35 +1,46 @@
+use crate::errno::Errno;
 use derive_new::new;
 #[allow(unused_imports)]
 use log::*;
-use nix::{
-    errno::Errno,
-    ioctl_readwrite,
-};
 use std::{
     collections::HashSet,
+    io,
+    ops::Deref,
     os::fd::AsRawFd as _,
+    result,
+    thread::{
+        self,
+        Builder,
+    },
     time::{
+        Duration,
+        Instant,
         SystemTime,
         UNIX_EPOCH,
     },
 };
 
+#[cfg(mutex)]
+use crate::Mutex;
 use crate::{
     Error,
     Event,
     EventSources,
-    NTSYNC_MAGIC,
+    Fd,
+    NTSyncObjects as _,
     NtSync,
     NtSyncFlags,
     OwnerId,
     Result,
+    abi,
     cold_path,
+    ioctl::ioctl_readwrite,
     raw,
 };
 
 #[repr(C)]
 #[derive(Debug, new)]
-struct WaitArgs {
+pub(crate) struct WaitArgs {
     timeout: u64,
     objs: u64,
     count: u32,
@@ -41,29 +52,488 @@ struct WaitArgs {
     pad: u32,
 }
 
+impl WaitArgs {
+    /// Checks the invariants between the arguments and the ids in debug builds, before and after the ioctl.
+    ///
+    /// `debug_assert` is not affected by the forbidden `clippy::panic` lint and compiled out in release builds.
+    fn debug_check(&self, ids: &[u64]) {
+        debug_assert_eq!(ids.len(), self.count as usize, "The count does not match the number of ids");
+        debug_assert_eq!(ids.as_ptr() as u64, self.objs, "The arguments do not point to the ids");
+        debug_assert!(self.alert == 0 || !ids.contains(&(self.alert as u64)), "The alert is part of the ids");
+        debug_assert!(self.index <= self.count, "The index is out of the range of the ids");
+    }
+}
+
 #[derive(Debug, Clone)]
+/// The result of an successful [wait_all](NtSync::wait_all).
+///
+/// The [objects](WaitAllStatus::objects) are sorted by the ordering of [EventSources], so two waits on the same set report them in the same order.
 pub struct WaitAllStatus {
     /// if true the Alert stopped the wait
     pub alerted: bool,
-    /// The objects as they were given, so the aquired resources can be freed.
-    pub objects: Vec<EventSources>,
+    objects: Vec<EventSources>,
+    alert: Option<Event>,
 }
 
 #[derive(Debug, Clone)]
+/// The result of an successful [wait_any](NtSync::wait_any).
+///
+/// The [objects](WaitAnyStatus::objects) are sorted by the ordering of [EventSources] and passed to the kernel in that order,
+/// so `objects()[index]` is the object that stopped the wait and two waits on the same set report the objects in the same order.
 pub struct WaitAnyStatus {
     /// if true the Alert stopped the wait
     pub alerted: bool,
-    /// The objects in the order they were processed
-    pub objects: Vec<EventSources>,
-    /// the number of the event that stopped the wait. 0 if the Alert stopped it.
+    objects: Vec<EventSources>,
+    /// the index of the object in [objects](WaitAnyStatus::objects) that stopped the wait. 0 if the Alert stopped it.
     pub index: u32,
+    alert: Option<Event>,
+}
+
+#[derive(Debug, Clone)]
+/// The result of [wait_any2](NtSync::wait_any2) and [wait_all2](NtSync::wait_all2), which only return the failures of the wait as errors.
+pub enum WaitOutcome<S> {
+    /// The objects were acquired.
+    Acquired(S),
+    /// The timeout was reached before the objects could be acquired.
+    TimedOut,
+    /// The alert stopped the wait.
+    Alerted,
+    /// The objects were acquired, but an mutex among them was abandoned by an killed owner, so the state it protects can be inconsistent.
+    Abandoned(S),
+}
+
+impl<S> WaitOutcome<S> {
+    fn from_kernel(result: Result<(S, bool)>, alerted: impl FnOnce(&S) -> bool, operation: &'static str) -> Result<Self> {
+        match result {
+            Ok((status, true)) => Ok(WaitOutcome::Abandoned(status)),
+            Ok((status, false)) if alerted(&status) => Ok(WaitOutcome::Alerted),
+            Ok((status, false)) => Ok(WaitOutcome::Acquired(status)),
+            Err(Error::Timeout) => Ok(WaitOutcome::TimedOut),
+            Err(error) => Err(error.with_context(operation)),
+        }
+    }
+
+    /// Returns the status if the objects were acquired, also if an mutex was abandoned.
+    pub fn status(self) -> Option<S> {
+        match self {
+            WaitOutcome::Acquired(status) | WaitOutcome::Abandoned(status) => Some(status),
+            WaitOutcome::TimedOut | WaitOutcome::Alerted => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Describes an retry of [wait_any_with_hook](NtSync::wait_any_with_hook) after the wait was interrupted by an signal.
+pub struct RetryInfo {
+    /// The number of the retry, starting at 1.
+    pub attempt: u32,
+    /// The time since the first attempt started.
+    pub elapsed: Duration,
+}
+
+/// The most objects the kernel accepts in one wait, `NTSYNC_MAX_WAIT_COUNT` in the kernel headers.
+pub const MAX_WAIT_COUNT: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A reason why the kernel would reject an wait, found by [validate_wait_set](NtSync::validate_wait_set).
+pub enum WaitSetProblem {
+    /// The set is empty.
+    Empty,
+    /// The set contains more than [MAX_WAIT_COUNT] objects.
+    TooManyObjects(usize),
+    /// The file descriptor of the object is closed, usually because the object was deleted.
+    Closed(EventSources),
+    /// The file descriptor is open, but it is not an ntsync object of the expected type. This happens when the number was reused after the object was deleted.
+    NotAnObject(EventSources),
+    /// The set contains an mutex, but the owner is missing or zero.
+    MissingOwner(EventSources),
+    /// The alert is also part of the set.
+    DuplicateAlert(Event),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The readiness of the objects of an wait that timed out, returned by [wait_any_diagnostic](NtSync::wait_any_diagnostic).
+///
+/// The objects are read after the timeout, so an object that became ready in between is listed in `satisfied_after`.
+pub struct TimeoutReport {
+    /// The objects that were not ready when they were read.
+    pub unsatisfied: Vec<EventSources>,
+    /// The objects that were ready when they were read, so they became ready right after the timeout.
+    pub satisfied_after: Vec<EventSources>,
+}
+
+/// Undoes the acquisition of an object by an wait, so another wait can acquire it.
+fn give_back(object: EventSources, _owner: Option<OwnerId>) -> Result<()> {
+    match object {
+        #[cfg(mutex)]
+        EventSources::Mutex(mutex) => mutex.unlock(_owner.unwrap_or_default()),
+        #[cfg(semaphore)]
+        EventSources::Semaphore(semaphore) => semaphore.release(1).map(|_| ()),
+        EventSources::Event(event) => event.signal().map(|_| ()),
+    }
+}
+
+/// Resets the alert of an wait if it stopped the wait.
+fn reset_alert(alerted: bool, alert: Option<Event>) -> Result<bool> {
+    match alert {
+        Some(alert) if alerted => {
+            alert.reset()?;
+            Ok(true)
+        },
+        _ => Ok(false),
+    }
+}
+
+impl WaitAllStatus {
+    /// The objects of the wait in the sorted order, so the aquired resources can be freed.
+    pub fn objects(&self) -> &[EventSources] {
+        &self.objects
+    }
+
+    /// Resets the alert if it stopped the wait, so the next wait with the same alert blocks again.
+    ///
+    /// Returns true if the alert was reset. See [wait_all](NtSync::wait_all) for the lifecycle of an alert.
+    pub fn reset_alert(&self) -> Result<bool> {
+        reset_alert(self.alerted, self.alert)
+    }
+
+    #[cfg(mutex)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "mutex")))]
+    /// Returns the recursion depth of an mutex that was acquired by this wait, read right after the wait.
+    ///
+    /// The depth is read with an separate status read, so it is only exact if no other wait of the same owner locked or unlocked the mutex in between.
+    /// Other owners can not change it while the mutex is held. If the mutex is not part of the wait or it was alerted, [Error::InvalidValue] is returned.
+    pub fn mutex_depth(&self, mutex: &Mutex) -> Result<u32> {
+        if self.alerted || !self.objects.contains(&EventSources::Mutex(*mutex)) {
+            cold_path();
+            return Err(Error::InvalidValue);
+        }
+        Ok(mutex.read()?.recursion_count())
+    }
+}
+
+impl WaitAnyStatus {
+    /// The objects of the wait in the sorted order, which is the order the kernel processed them in.
+    pub fn objects(&self) -> &[EventSources] {
+        &self.objects
+    }
+
+    /// Returns the object that stopped the wait, or [None] if the alert stopped it.
+    ///
+    /// This is `objects()[index]`, so the caller does not have to know the order of the objects.
+    pub fn triggered(&self) -> Option<&EventSources> {
+        if self.alerted {
+            return None;
+        }
+        self.objects.get(self.index as usize)
+    }
+
+    /// Resets the alert if it stopped the wait, so the next wait with the same alert blocks again.
+    ///
+    /// Returns true if the alert was reset. See [wait_all](NtSync::wait_all) for the lifecycle of an alert.
+    pub fn reset_alert(&self) -> Result<bool> {
+        reset_alert(self.alerted, self.alert)
+    }
+}
+
+#[derive(Debug)]
+#[must_use = "the objects are freed when the guard is dropped"]
+/// Frees every object of an set that is owned by the caller when it is dropped, created with [draining](NtSync::draining).
+///
+/// The objects are freed with [free](EventSources::free) whether they were acquired or not, so it is meant for the set of an successful [wait_all](NtSync::wait_all).
+/// Freeing an object that was not acquired fails for an mutex of another owner with [Error::WrongOwner], overflows an semaphore or resets an event.
+pub struct Draining<'a> {
+    set: &'a HashSet<EventSources>,
+    owner: OwnerId,
+}
+
+impl Draining<'_> {
+    /// Returns the set that is freed on drop.
+    pub fn set(&self) -> &HashSet<EventSources> {
+        self.set
+    }
+
+    /// Returns the owner that is used to unlock the mutexes.
+    pub fn owner(&self) -> OwnerId {
+        self.owner
+    }
+}
+
+impl Drop for Draining<'_> {
+    fn drop(&mut self) {
+        for object in self.set {
+            if let Err(error) = object.free(self.owner) {
+                cold_path();
+                warn!(target: "ntsync", handle=object.as_raw_fd(); "Failed to free an object of an draining set: {error}");
+            }
+        }
+    }
+}
+
+/// Converts the timeout of the [SystemTime] based functions into the nanoseconds the kernel expects.
+///
+/// An point in time after the year 2554 does not fit into the nanoseconds and saturates to [u64::MAX], which never times out.
+/// An point before [UNIX_EPOCH] is already over, so it times out immediately.
+fn timeout_ns(timeout: Option<SystemTime>) -> u64 {
+    match timeout {
+        Some(timeout) => timeout.duration_since(UNIX_EPOCH).map_or(0, |since| u64::try_from(since.as_nanos()).unwrap_or(u64::MAX)),
+        None => u64::MAX,
+    }
+}
+
+/// Computes the absolute deadline for an relative `timeout` on the clock the kernel uses for the wait.
+///
+/// With [NtSyncFlags::WaitRealtime] the base is `CLOCK_REALTIME`, otherwise `CLOCK_MONOTONIC`. [None] means no timeout.
+fn deadline_ns(timeout: Option<Duration>, flags: &NtSyncFlags) -> u64 {
+    let Some(timeout) = timeout else {
+        return u64::MAX;
+    };
+    u64::try_from(clock_now(flags).saturating_add(timeout).as_nanos()).unwrap_or(u64::MAX)
+}
+
+#[cfg(record)]
+/// Returns the time that is left until `deadline_ns`, [None] if the wait never times out.
+fn remaining(deadline_ns: u64, flags: &NtSyncFlags) -> Option<Duration> {
+    (deadline_ns != u64::MAX).then(|| Duration::from_nanos(deadline_ns).saturating_sub(clock_now(flags)))
+}
+
+/// Reads the clock that `flags` select for the timeout.
+fn clock_now(flags: &NtSyncFlags) -> Duration {
+    let clock = if flags.contains(NtSyncFlags::WaitRealtime) {
+        libc::CLOCK_REALTIME
+    } else {
+        libc::CLOCK_MONOTONIC
+    };
+    let mut now = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    if let Err(errno) = Errno::result(unsafe { libc::clock_gettime(clock, &raw mut now) }) {
+        cold_path();
+        warn!(target: "ntsync", returncode=errno as i32; "Failed to read the clock, the wait times out immediately");
+    }
+    Duration::new(now.tv_sec as u64, now.tv_nsec as u32)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// An absolute timeout in nanoseconds, that is passed to the kernel without any conversion.
+///
+/// It is measured on the clock the flags of the wait select, see [wait_all_for](NtSync::wait_all_for).
+pub struct RawTimeout(pub u64);
+
+/// Converts the different kinds of timeouts into the absolute nanoseconds the kernel expects.
+///
+/// The kernel measures the timeout on `CLOCK_REALTIME` with [NtSyncFlags::WaitRealtime] and on `CLOCK_MONOTONIC` without it,
+/// so the conversion gets the flags of the wait. [None] means that the wait never times out.
+pub trait IntoTimeout {
+    /// Returns the deadline in nanoseconds on the clock that `flags` select.
+    fn into_timeout_ns(self, flags: &NtSyncFlags) -> u64;
+}
+
+impl IntoTimeout for Option<SystemTime> {
+    /// An point in realtime. Without [NtSyncFlags::WaitRealtime] the remaining time is added to the monotonic clock.
+    fn into_timeout_ns(self, flags: &NtSyncFlags) -> u64 {
+        match self {
+            Some(timeout) if !flags.contains(NtSyncFlags::WaitRealtime) => {
+                deadline_ns(Some(timeout.duration_since(SystemTime::now()).unwrap_or_default()), flags)
+            },
+            timeout => timeout_ns(timeout),
+        }
+    }
+}
+
+impl IntoTimeout for Option<Instant> {
+    /// An point on the monotonic clock of the process, the remaining time is added to the clock the flags select.
+    fn into_timeout_ns(self, flags: &NtSyncFlags) -> u64 {
+        deadline_ns(self.map(|timeout| timeout.saturating_duration_since(Instant::now())), flags)
+    }
+}
+
+impl IntoTimeout for Option<Duration> {
+    /// An timeout relative to the start of the wait.
+    fn into_timeout_ns(self, flags: &NtSyncFlags) -> u64 {
+        deadline_ns(self, flags)
+    }
+}
+
+impl IntoTimeout for RawTimeout {
+    fn into_timeout_ns(self, _flags: &NtSyncFlags) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(random)]
+#[cfg_attr(docsrs, doc(cfg(feature = "random")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// An timeout relative to the start of the wait, that is moved randomly by up to `percent` of its length in both directions.
+///
+/// Threads that wait with the same timeout and retry after it, all wake up at the same time and compete for the objects again.
+/// With an jittered timeout the retries spread out. The random numbers come from [rand], so it needs the `random` feature.
+/// Each conversion with [IntoTimeout] draws an new timeout.
+pub struct JitteredTimeout {
+    base: Duration,
+    percent: u8,
+}
+
+#[cfg(random)]
+impl JitteredTimeout {
+    /// Creates the timeout. An `percent` above 100 is lowered to 100, so the timeout can become zero, but never negative.
+    pub fn new(base: Duration, percent: u8) -> Self {
+        JitteredTimeout {
+            base,
+            percent: percent.min(100),
+        }
+    }
+
+    /// Returns the timeout without the jitter.
+    pub fn base(&self) -> Duration {
+        self.base
+    }
+
+    /// Returns the largest change of the timeout in percent of the base.
+    pub fn percent(&self) -> u8 {
+        self.percent
+    }
+
+    /// Draws an timeout between `base - percent` and `base + percent`.
+    pub fn sample(&self) -> Duration {
+        let spread = self.base.as_nanos() * u128::from(self.percent) / 100;
+        let lowest = self.base.as_nanos() - spread;
+        let nanos = rand::random_range(lowest..=self.base.as_nanos() + spread);
+        Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+    }
+}
+
+#[cfg(random)]
+impl IntoTimeout for JitteredTimeout {
+    /// An new [sample](JitteredTimeout::sample) relative to the start of the wait.
+    fn into_timeout_ns(self, flags: &NtSyncFlags) -> u64 {
+        deadline_ns(Some(self.sample()), flags)
+    }
+}
+
+/// The ids that are passed to the kernel. A single id is kept on the stack, because most waits are on one object.
+enum Ids {
+    One([u64; 1]),
+    Many(Vec<u64>),
+}
+
+impl Deref for Ids {
+    type Target = [u64];
+
+    fn deref(&self) -> &[u64] {
+        match self {
+            Ids::One(id) => id,
+            Ids::Many(ids) => ids,
+        }
+    }
+}
+
+/// Checks one source and returns the id that is passed to the kernel.
+fn source_id(source: EventSources, _owner: Option<OwnerId>, alertid: Fd) -> Result<u64> {
+    match source {
+        EventSources::Event(event) => {
+            if alertid != 0 && alertid == event.id {
+                return Err(Error::DuplicateEvent);
+            }
+            Ok(event.id as u64)
+        },
+        #[cfg(semaphore)]
+        EventSources::Semaphore(semaphore) => Ok(semaphore.id as u64),
+
+        #[cfg(mutex)]
+        EventSources::Mutex(mutex) => {
+            if _owner.is_none_or(|val| val.0 == 0) {
+                error!(target: "ntsync", "Invalid Owner. Owner must be an non Zero value");
+                return Err(Error::InvalidValue);
+            }
+            Ok(mutex.id as u64)
+        },
+    }
+}
+
+/// Checks the sources and collects the ids that are passed to the kernel.
+///
+/// Returns the sources in the order of the ids and the id of the alert, which is 0 without an alert.
+/// An single source skips the sorting and the allocation of the ids.
+fn collect_ids(sources: HashSet<EventSources>, owner: Option<OwnerId>, alert: Option<Event>) -> Result<(Vec<EventSources>, Ids, Fd)> {
+    let alertid = alert
+        .unwrap_or(Event {
+            id: 0,
+            manual: None,
+        })
+        .id;
+    if sources.len() == 1 &&
+        let Some(source) = sources.iter().next().copied()
+    {
+        let id = source_id(source, owner, alertid)?;
+        return Ok((vec![source], Ids::One([id]), alertid));
+    }
+    if sources.is_empty() {
+        cold_path();
+        return Err(Error::EmptyWaitSet);
+    }
+    let mut sorted: Vec<EventSources> = sources.into_iter().collect();
+    sorted.sort_unstable();
+    let mut ids = Vec::with_capacity(sorted.len());
+    for source in &sorted {
+        ids.push(source_id(*source, owner, alertid)?);
+    }
+    Ok((sorted, Ids::Many(ids), alertid))
+}
+
+/// Logs and returns [Error::DeviceGone] for an wait that was rejected with `EBADF`.
+///
+/// The wait ioctls run on the device and the kernel reports closed objects in the set with `EINVAL`, so `EBADF` always means that the device itself was closed.
+fn device_gone(instance: &NtSync) -> Error {
+    cold_path();
+    debug!(target: "ntsync", handle=instance.inner.handle.as_raw_fd(); "The device was closed before the wait");
+    Error::DeviceGone
+}
+
+/// Returns the error for an wait that was rejected with `EINVAL`.
+///
+/// The kernel does not tell which object was invalid, so the alert is read to tell an closed alert apart from the other invalid arguments.
+/// The read only happens on the error path, so valid waits do not pay for it.
+fn invalid_wait(alert: Option<Event>) -> Error {
+    cold_path();
+    match alert.map(|alert| alert.status()) {
+        Some(Err(error)) => {
+            debug!(target: "ntsync", handle=alert.map_or(0, |alert| alert.id); "The alert of the wait is invalid: {error}");
+            Error::AlertInvalid
+        },
+        _ => Error::InvalidValue,
+    }
 }
 
 impl NtSync {
+    /// Returns an guard that frees all objects of `set` with `owner` when it is dropped.
+    ///
+    /// The set is borrowed, so it has to be kept by the caller, for example because it is waited on again later.
+    pub fn draining<'a>(&self, set: &'a HashSet<EventSources>, owner: OwnerId) -> Draining<'a> {
+        Draining {
+            set,
+            owner,
+        }
+    }
+
     /// this function waits until all sources are free/triggered.
     /// It is the reason [NtSync::wait_any] also has an [`std::collections::HashSet`] in its signature.
     /// the Kernel Driver reacts with duplicate Values in its event sources or an Event that is both an object and an alert.
     /// this implementation prevents it by making it impossible to reach that state.
+    ///
+    /// An empty set is rejected with [Error::EmptyWaitSet] without asking the kernel, which would only return an generic [Error::InvalidValue].
+    ///
+    /// The `alert` stops the wait when it is signaled. An manual reset alert stays signaled after it stopped an wait,
+    /// so every following wait with it returns alerted immediately until it is reset.
+    /// This is wanted if one alert cancels many waits, otherwise reset it with [WaitAllStatus::reset_alert] or [WaitAnyStatus::reset_alert].
+    /// An auto reset alert is reset by the kernel when it stops an wait.
+    /// An alert that was closed before the wait fails with [Error::AlertInvalid]. Closing it during the wait does not stop the wait,
+    /// because the kernel holds its own reference, so it only ends through the objects or the timeout.
+    ///
+    /// If the sources contain an mutex the owner must not be zero. [NonZeroOwnerId](crate::NonZeroOwnerId) ensures that when the owner is created.
+    ///
+    /// Errors carry `"wait_all"` as [context](Error::with_context), so match on [kind](Error::kind) instead of the error itself.
     pub fn wait_all(
         &self,
         sources: HashSet<EventSources>,
@@ -72,141 +542,605 @@ impl NtSync {
         flags: NtSyncFlags,
         alert: Option<Event>,
     ) -> Result<WaitAllStatus> {
-        let mut return_sources = Vec::with_capacity(sources.len());
-        let mut ids = Vec::new();
+        self.wait_all_with(sources, timeout, owner, flags, alert)
+    }
 
-        let alertid = alert
-            .unwrap_or(Event {
-                id: 0,
-            })
-            .id;
-        for source in sources {
-            return_sources.push(source);
-            match source {
-                EventSources::Event(event) => {
-                    if alertid != 0 && alertid == event.id {
-                        return Err(Error::DuplicateEvent);
-                    }
-                    ids.push(event.id as u64)
-                },
-                #[cfg(semaphore)]
-                EventSources::Semaphore(semaphore) => ids.push(semaphore.id as u64),
+    /// Works like [wait_all](NtSync::wait_all), but accepts every timeout that implements [IntoTimeout].
+    ///
+    /// `wait_all` and the other variants keep their concrete timeout type, so an bare [None] can be passed to them.
+    pub fn wait_all_with(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: impl IntoTimeout,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+    ) -> Result<WaitAllStatus> {
+        let deadline = timeout.into_timeout_ns(&flags);
+        self.wait_all_raw_timeout(sources, deadline, owner, flags, alert)
+    }
 
-                #[cfg(mutex)]
-                EventSources::Mutex(mutex) => {
-                    if owner.is_none_or(|val| val.0 == 0) {
-                        error!(target: "ntsync", "Invalid Owner. Owner must be an non Zero value");
-                        return Err(Error::InvalidValue);
-                    }
-                    ids.push(mutex.id as u64);
-                },
-            }
+    /// Works like [wait_all](NtSync::wait_all), but the timeout is passed to the kernel without any conversion.
+    ///
+    /// See [wait_any_raw_timeout](NtSync::wait_any_raw_timeout) for the meaning of `timeout_ns`.
+    pub fn wait_all_raw_timeout(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout_ns: u64,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+    ) -> Result<WaitAllStatus> {
+        match self.wait_all_kernel(sources, timeout_ns, owner, flags, alert) {
+            Ok((status, false)) => Ok(status),
+            Ok((_, true)) => Err(Error::OwnerDead.with_context("wait_all")),
+            Err(error) => Err(error.with_context("wait_all")),
         }
-        let mut args = WaitArgs::new(
-            timeout.and_then(|st| st.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).ok()).unwrap_or(u64::MAX),
-            ids.as_ptr() as u64,
-            ids.len() as u32,
-            0,
-            flags.bits(),
-            owner.unwrap_or_default().0,
-            alertid as u32,
-        );
-        match unsafe { ntsync_wait_all(self.inner.handle.as_raw_fd(), raw!(mut args: WaitArgs)) } {
-            Ok(_) => {
-                Ok(WaitAllStatus {
-                    alerted: args.index == args.count,
-                    objects: return_sources,
-                })
-            },
+    }
+
+    /// Issues the wait and returns the status together with true if an abandoned mutex was acquired, which the kernel reports with `EOWNERDEAD`.
+    fn wait_all_kernel(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout_ns: u64,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+    ) -> Result<(WaitAllStatus, bool)> {
+        let (return_sources, ids, alertid) = collect_ids(sources, owner, alert)?;
+        let mut args = WaitArgs::new(timeout_ns, ids.as_ptr() as u64, ids.len() as u32, 0, flags.bits(), owner.unwrap_or_default().0, alertid as u32);
+        args.debug_check(&ids);
+        #[cfg(metrics)]
+        let _registration = self.inner.waits.register(&return_sources, owner, true);
+        let result = unsafe { ntsync_wait_all(self.inner.handle.as_raw_fd(), raw!(mut args: WaitArgs)) };
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::WaitAll {
+                    objects: return_sources.iter().map(|source| recorder.object(*source)).collect(),
+                    timeout: remaining(timeout_ns, &flags),
+                    owner: args.owner,
+                    flags: args.flags,
+                    alert: alert.map(|alert| recorder.object(alert.into())),
+                },
+                crate::record::Outcome::of(&result, || {
+                    if args.index == args.count {
+                        crate::record::Outcome::Alerted
+                    } else {
+                        crate::record::Outcome::Done
+                    }
+                }),
+            )
+        });
+        let abandoned = match result {
+            Ok(_) => false,
+            Err(Errno::EOWNERDEAD) => true,
             Err(errno) => {
                 cold_path();
-                match errno {
-                    Errno::EINVAL => Err(Error::InvalidValue),
-                    Errno::EOWNERDEAD => Err(Error::OwnerDead),
+                return match errno {
+                    Errno::EINVAL => Err(invalid_wait(alert)),
                     Errno::ETIMEDOUT => Err(Error::Timeout),
+                    Errno::EBADF => Err(device_gone(self)),
                     other => {
                         cold_path();
                         Err(Error::Unknown(other as i32))
                     },
-                }
+                };
+            },
+        };
+        args.debug_check(&ids);
+        #[cfg(debug_semaphore)]
+        if args.index != args.count {
+            crate::accounting::acquired(&return_sources);
+        }
+        Ok((
+            WaitAllStatus {
+                alerted: args.index == args.count,
+                objects: return_sources,
+                alert,
             },
+            abandoned,
+        ))
+    }
+
+    /// Works like [wait_all](NtSync::wait_all), but returns the timeout, the alert and an abandoned mutex as [WaitOutcome] instead of an error or the `alerted` field.
+    ///
+    /// Only the failures of the wait itself are returned as [Error].
+    pub fn wait_all2(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: Option<SystemTime>,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+    ) -> Result<WaitOutcome<WaitAllStatus>> {
+        let deadline = timeout.into_timeout_ns(&flags);
+        WaitOutcome::from_kernel(self.wait_all_kernel(sources, deadline, owner, flags, alert), |status| status.alerted, "wait_all")
+    }
+
+    /// Works like [wait_all](NtSync::wait_all), but fails with [Error::WouldBlock] instead of blocking if an semaphore of the set has no permit left.
+    ///
+    /// The counts are read before the wait, so only the other objects, for example an mutex that is briefly held, may block until the timeout.
+    /// This check is racy: another thread can take the last permit between the read and the wait, which then blocks like an normal [wait_all](NtSync::wait_all).
+    /// Without semaphores in the set this is an plain [wait_all](NtSync::wait_all).
+    #[cfg(semaphore)]
+    #[cfg_attr(docsrs, doc(cfg(feature = "semaphore")))]
+    pub fn wait_all_nonblocking_semaphores(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: Option<SystemTime>,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+    ) -> Result<WaitAllStatus> {
+        for source in &sources {
+            if let EventSources::Semaphore(semaphore) = source &&
+                semaphore.read().map_err(|error| error.with_context("wait_all"))?.count == 0
+            {
+                cold_path();
+                debug!(target: "ntsync", handle=semaphore.id, kind="semaphore"; "The semaphore has no permit left, the wait is not started");
+                return Err(Error::WouldBlock.with_context("wait_all"));
+            }
         }
+        self.wait_all(sources, timeout, owner, flags, alert)
     }
 
-    /// this is similar to [NtSync::wait_all], but it will stop waiting once one Source triggers.
-    pub fn wait_any(
+    /// Checks the set of an wait for the problems that make the kernel reject it with [Error::InvalidValue].
+    ///
+    /// The kernel does not report which object caused the error, so this can be called after an wait failed unexpectedly to find the cause.
+    /// Each object is checked with `fcntl` for an open file descriptor and with an status read for its type.
+    /// Objects of another [NtSync] instance on the same device can not be detected.
+    pub fn validate_wait_set(&self, sources: &HashSet<EventSources>, owner: Option<OwnerId>, alert: Option<Event>) -> result::Result<(), Vec<WaitSetProblem>> {
+        let mut problems = Vec::new();
+        if sources.is_empty() {
+            problems.push(WaitSetProblem::Empty);
+        }
+        if sources.len() > MAX_WAIT_COUNT {
+            problems.push(WaitSetProblem::TooManyObjects(sources.len()));
+        }
+        let mut sorted: Vec<&EventSources> = sources.iter().collect();
+        sorted.sort();
+        for source in sorted {
+            if Errno::result(unsafe { libc::fcntl(source.as_raw_fd(), libc::F_GETFD) }).is_err() {
+                problems.push(WaitSetProblem::Closed(*source));
+                continue;
+            }
+            if let Err(error) = source.ready(owner) {
+                debug!(target: "ntsync", handle=source.as_raw_fd(); "Failed to read the status: {error}");
+                problems.push(WaitSetProblem::NotAnObject(*source));
+            }
+            match source {
+                #[cfg(mutex)]
+                EventSources::Mutex(_) if owner.is_none_or(|owner| owner.0 == 0) => problems.push(WaitSetProblem::MissingOwner(*source)),
+                EventSources::Event(event) if alert.is_some_and(|alert| alert.id == event.id) => problems.push(WaitSetProblem::DuplicateAlert(*event)),
+                _ => {},
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Works like [wait_all](NtSync::wait_all) with the owner of the calling thread from [OwnerId::current_thread].
+    ///
+    /// This covers the common case that the thread which waits also unlocks the mutexes later.
+    /// The owner is passed even if there is no mutex in the sources, which the kernel ignores.
+    pub fn wait_all_as_current_thread(
         &self,
         sources: HashSet<EventSources>,
         timeout: Option<SystemTime>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+    ) -> Result<WaitAllStatus> {
+        let owner = OwnerId::current_thread();
+        if owner.0 == 0 {
+            cold_path();
+            error!(target: "ntsync", "The thread id is zero and can not be used as owner");
+            return Err(Error::InvalidValue);
+        }
+        self.wait_all(sources, timeout, Some(owner), flags, alert)
+    }
+
+    /// Works like [wait_all](NtSync::wait_all), but the timeout is relative to the start of the wait.
+    ///
+    /// The deadline is computed on the clock that `flags` select, so the combination of flags and timeout types is:
+    ///
+    /// | timeout | without [WaitRealtime](NtSyncFlags::WaitRealtime) | with [WaitRealtime](NtSyncFlags::WaitRealtime) |
+    /// |---|---|---|
+    /// | [Duration] (`wait_*_for`) | `CLOCK_MONOTONIC` + timeout | `CLOCK_REALTIME` + timeout |
+    /// | [SystemTime] (`wait_all`/`wait_any`) | `CLOCK_MONOTONIC` + the remaining time | absolute `CLOCK_REALTIME` |
+    /// | [Instant] | `CLOCK_MONOTONIC` + the remaining time | `CLOCK_REALTIME` + the remaining time |
+    /// | raw nanoseconds (`wait_*_raw_timeout`, [RawTimeout]) | absolute `CLOCK_MONOTONIC` | absolute `CLOCK_REALTIME` |
+    ///
+    /// All of them implement [IntoTimeout] and can be passed to [wait_all_with](NtSync::wait_all_with) and [wait_any_with](NtSync::wait_any_with).
+    pub fn wait_all_for(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: Option<Duration>,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+    ) -> Result<WaitAllStatus> {
+        self.wait_all_with(sources, timeout, owner, flags, alert)
+    }
+
+    /// Works like [wait_any](NtSync::wait_any), but the timeout is relative to the start of the wait.
+    ///
+    /// See [wait_all_for](NtSync::wait_all_for) for the clock that is used.
+    pub fn wait_any_for(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: Option<Duration>,
         owner: Option<OwnerId>,
         flags: NtSyncFlags,
         alert: Option<Event>,
     ) -> Result<WaitAnyStatus> {
-        let mut return_sources = Vec::with_capacity(sources.len());
-        let mut ids = Vec::new();
+        self.wait_any_with(sources, timeout, owner, flags, alert)
+    }
 
-        let alertid = alert
-            .unwrap_or(Event {
-                id: 0,
+    /// Reports for each object if it could be acquired at this moment, using the status reads of the objects.
+    ///
+    /// This is only an snapshot. Other threads can change the state of the objects between the reads and any later wait.
+    /// Objects whose status could not be read, for example because they are deleted, are reported as not ready.
+    pub fn readiness(&self, sources: &[EventSources], owner: Option<OwnerId>) -> Vec<(EventSources, bool)> {
+        sources
+            .iter()
+            .map(|source| {
+                let ready = source.ready(owner).unwrap_or_else(|error| {
+                    debug!(target: "ntsync", "Failed to read the status of {source:?}: {error}");
+                    false
+                });
+                (*source, ready)
             })
-            .id;
+            .collect()
+    }
+
+    /// Returns the objects that an wait with `owner` could acquire at this moment without blocking.
+    ///
+    /// The objects are only read with their status ioctls, so no signal of an automatic reset event and no permit of an semaphore is consumed.
+    /// It is an racy snapshot, other threads can change the objects between the reads and any later wait.
+    /// Unlike [readiness](NtSync::readiness) the first object whose status can not be read fails the whole query.
+    pub fn ready_subset(&self, sources: &HashSet<EventSources>, owner: Option<OwnerId>) -> Result<HashSet<EventSources>> {
+        let mut ready = HashSet::new();
         for source in sources {
-            return_sources.push(source);
-            match source {
-                EventSources::Event(event) => {
-                    if alertid != 0 && alertid == event.id {
-                        return Err(Error::DuplicateEvent);
+            if source.ready(owner)? {
+                ready.insert(*source);
+            }
+        }
+        Ok(ready)
+    }
+
+    /// Splits the objects into the ones that could be acquired at this moment and the ones that could not, like [readiness](NtSync::readiness).
+    pub fn timeout_report(&self, sources: &[EventSources], owner: Option<OwnerId>) -> TimeoutReport {
+        let (satisfied_after, unsatisfied): (Vec<_>, Vec<_>) = self.readiness(sources, owner).into_iter().partition(|(_, ready)| *ready);
+        TimeoutReport {
+            unsatisfied: unsatisfied.into_iter().map(|(source, _)| source).collect(),
+            satisfied_after: satisfied_after.into_iter().map(|(source, _)| source).collect(),
+        }
+    }
+
+    /// Works like [wait_any](NtSync::wait_any), but reports the readiness of every object if the wait timed out.
+    ///
+    /// The outer [Result](std::result::Result) contains the errors of the wait, an timeout is returned as the [TimeoutReport] in the inner one.
+    /// The objects are read one after another after the timeout, so other threads can change them in between and the report is only an hint,
+    /// for example to find the object that keeps an wait from succeeding.
+    pub fn wait_any_diagnostic(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: Option<SystemTime>,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+    ) -> Result<result::Result<WaitAnyStatus, TimeoutReport>> {
+        let objects: Vec<EventSources> = sources.iter().copied().collect();
+        match self.wait_any(sources, timeout, owner, flags, alert) {
+            Ok(status) => Ok(Ok(status)),
+            Err(error) if matches!(error.kind(), Error::Timeout) => {
+                let report = self.timeout_report(&objects, owner);
+                debug!(target: "ntsync", "The wait timed out, {} objects were ready afterwards", report.satisfied_after.len());
+                Ok(Err(report))
+            },
+            Err(error) => Err(error),
+        }
+    }
+
+    /// this is similar to [NtSync::wait_all], but it will stop waiting once one Source triggers.
+    ///
+    /// An empty set is rejected with [Error::EmptyWaitSet] like in [NtSync::wait_all]. Errors carry `"wait_any"` as [context](Error::with_context).
+    pub fn wait_any(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: Option<SystemTime>,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+    ) -> Result<WaitAnyStatus> {
+        self.wait_any_with(sources, timeout, owner, flags, alert)
+    }
+
+    /// Works like [wait_any](NtSync::wait_any), but accepts every timeout that implements [IntoTimeout].
+    pub fn wait_any_with(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: impl IntoTimeout,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+    ) -> Result<WaitAnyStatus> {
+        let deadline = timeout.into_timeout_ns(&flags);
+        self.wait_any_raw_timeout(sources, deadline, owner, flags, alert)
+    }
+
+    /// Works like [wait_any](NtSync::wait_any), but an wait that was interrupted by an signal is issued again and `on_retry` is called before each new attempt.
+    ///
+    /// The deadline is computed once, so the retries do not extend the timeout. Every other error is returned like from `wait_any`.
+    /// The hook runs on the waiting thread between two attempts, so it should not block.
+    pub fn wait_any_with_hook(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: Option<SystemTime>,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+        mut on_retry: impl FnMut(RetryInfo),
+    ) -> Result<WaitAnyStatus> {
+        let deadline = timeout.into_timeout_ns(&flags);
+        let started = Instant::now();
+        let mut attempt = 0;
+        loop {
+            match self.wait_any_raw_timeout(sources.clone(), deadline, owner, flags, alert) {
+                Err(error) if matches!(error.kind(), Error::Interrupt) => {
+                    cold_path();
+                    attempt += 1;
+                    let info = RetryInfo {
+                        attempt,
+                        elapsed: started.elapsed(),
+                    };
+                    trace!(target: "ntsync", "The wait was interrupted, retrying attempt {attempt} after {:?}", info.elapsed);
+                    on_retry(info);
+                },
+                result => return result,
+            }
+        }
+    }
+
+    /// Works like [wait_any](NtSync::wait_any), but the timeout is passed to the kernel without any conversion.
+    ///
+    /// `timeout_ns` is an absolute point in time in nanoseconds of the clock selected by `flags`:
+    /// [CLOCK_REALTIME](libc::CLOCK_REALTIME) with [NtSyncFlags::WaitRealtime] and [CLOCK_MONOTONIC](libc::CLOCK_MONOTONIC) without it.
+    /// The value is not checked, so the caller has to make sure it matches the clock.
+    /// [u64::MAX] still means that the wait never times out.
+    pub fn wait_any_raw_timeout(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout_ns: u64,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+    ) -> Result<WaitAnyStatus> {
+        match self.wait_any_kernel(sources, timeout_ns, owner, flags, alert) {
+            Ok((status, false)) => Ok(status),
+            Ok((_, true)) => Err(Error::OwnerDead.with_context("wait_any")),
+            Err(error) => Err(error.with_context("wait_any")),
+        }
+    }
+
+    /// Issues the wait and returns the status together with true if an abandoned mutex was acquired, which the kernel reports with `EOWNERDEAD`.
+    fn wait_any_kernel(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout_ns: u64,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+    ) -> Result<(WaitAnyStatus, bool)> {
+        let (return_sources, ids, alertid) = collect_ids(sources, owner, alert)?;
+        let mut args = WaitArgs::new(timeout_ns, ids.as_ptr() as u64, ids.len() as u32, 0, flags.bits(), owner.unwrap_or_default().0, alertid as u32);
+        args.debug_check(&ids);
+        #[cfg(metrics)]
+        let _registration = self.inner.waits.register(&return_sources, owner, false);
+        let result = unsafe { ntsync_wait_any(self.inner.handle.as_raw_fd(), raw!(mut args: WaitArgs)) };
+        #[cfg(record)]
+        crate::record::record(|recorder| {
+            (
+                crate::record::Operation::WaitAny {
+                    objects: return_sources.iter().map(|source| recorder.object(*source)).collect(),
+                    timeout: remaining(timeout_ns, &flags),
+                    owner: args.owner,
+                    flags: args.flags,
+                    alert: alert.map(|alert| recorder.object(alert.into())),
+                },
+                crate::record::Outcome::of(&result, || {
+                    match return_sources.get(args.index as usize) {
+                        Some(source) => crate::record::Outcome::Signaled(recorder.object(*source)),
+                        None => crate::record::Outcome::Alerted,
                     }
-                    ids.push(event.id as u64)
+                }),
+            )
+        });
+        let abandoned = match result {
+            Ok(_) => false,
+            Err(Errno::EOWNERDEAD) => true,
+            Err(errno) => {
+                return match errno {
+                    Errno::EINTR => Err(Error::Interrupt),
+                    Errno::EBADF => Err(device_gone(self)),
+                    Errno::ETIMEDOUT => Err(Error::Timeout),
+                    Errno::EINVAL => Err(invalid_wait(alert)),
+                    other => Err(Error::Unknown(other as i32)),
+                };
+            },
+        };
+        args.debug_check(&ids);
+        #[cfg(debug_semaphore)]
+        crate::accounting::acquired(return_sources.get(args.index as usize));
+        Ok((
+            WaitAnyStatus {
+                alerted: args.index == args.count,
+                objects: return_sources,
+                alert,
+                index: if args.index == args.count {
+                    0
+                } else {
+                    args.index
                 },
-                #[cfg(semaphore)]
-                EventSources::Semaphore(semaphore) => ids.push(semaphore.id as u64),
+            },
+            abandoned,
+        ))
+    }
 
-                #[cfg(mutex)]
-                EventSources::Mutex(mutex) => {
-                    if owner.is_none_or(|val| val.0 == 0) {
-                        error!(target: "ntsync", "Invalid Owner. Owner must be an non Zero value");
-                        return Err(Error::InvalidValue);
+    /// Works like [wait_any](NtSync::wait_any), but returns the result as [WaitOutcome] like [wait_all2](NtSync::wait_all2).
+    ///
+    /// The abandoned mutex of an [Abandoned](WaitOutcome::Abandoned) outcome is the object at the [index](WaitAnyStatus::index) of the status.
+    pub fn wait_any2(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: Option<SystemTime>,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+        alert: Option<Event>,
+    ) -> Result<WaitOutcome<WaitAnyStatus>> {
+        let deadline = timeout.into_timeout_ns(&flags);
+        WaitOutcome::from_kernel(self.wait_any_kernel(sources, deadline, owner, flags, alert), |status| status.alerted, "wait_any")
+    }
+
+    /// Works like [wait_any](NtSync::wait_any), but accepts more than [MAX_WAIT_COUNT] objects.
+    ///
+    /// Sets within the limit are passed to [wait_any](NtSync::wait_any) directly.
+    /// Larger sets are split into chunks that are waited on by one helper thread each, and the first chunk that fires stops the others through an shared alert.
+    /// This costs an thread and an ioctl per chunk. When multiple chunks fire at the same time, the objects the later ones acquired are given back:
+    /// mutexes are unlocked, semaphores released and events signaled again.
+    /// An wait for all objects can not be split this way, because the chunks would not acquire their objects together.
+    pub fn wait_any_unbounded(
+        &self,
+        sources: HashSet<EventSources>,
+        timeout: Option<SystemTime>,
+        owner: Option<OwnerId>,
+        flags: NtSyncFlags,
+    ) -> Result<WaitAnyStatus> {
+        if sources.len() <= MAX_WAIT_COUNT {
+            return self.wait_any(sources, timeout, owner, flags, None);
+        }
+        let mut objects: Vec<EventSources> = sources.into_iter().collect();
+        objects.sort_unstable();
+        let done = self.new_event(false, true)?;
+        let results = thread::scope(|scope| {
+            let helpers: Vec<_> = objects
+                .chunks(MAX_WAIT_COUNT)
+                .map(|chunk| {
+                    let chunk = chunk.iter().copied().collect::<HashSet<_>>();
+                    Builder::new().name("ntsync chunk".to_owned()).spawn_scoped(scope, move || {
+                        let result = self.wait_any(chunk, timeout, owner, flags, Some(done));
+                        if !matches!(&result, Ok(status) if status.alerted) &&
+                            let Err(error) = done.signal()
+                        {
+                            cold_path();
+                            warn!(target: "ntsync", handle=done.id; "Failed to stop the other chunks: {error}");
+                        }
+                        result
+                    })
+                })
+                .collect();
+            helpers
+                .into_iter()
+                .map(|helper| {
+                    match helper {
+                        Ok(helper) => helper.join().unwrap_or_else(|_| Err(Error::IOError(io::Error::other("The thread of an chunk panicked")))),
+                        Err(error) => {
+                            cold_path();
+                            if let Err(error) = done.signal() {
+                                warn!(target: "ntsync", handle=done.id; "Failed to stop the other chunks: {error}");
+                            }
+                            Err(Error::IOError(error))
+                        },
+                    }
+                })
+                .collect::<Vec<_>>()
+        });
+        if let Err(error) = done.delete() {
+            cold_path();
+            warn!(target: "ntsync", handle=done.id; "Failed to delete the alert of the chunks: {error}");
+        }
+        let mut winner = None;
+        let mut failure = None;
+        for result in results {
+            match result.map_err(Error::into_kind) {
+                Ok(status) => {
+                    let Some(object) = status.triggered().copied() else {
+                        continue;
+                    };
+                    if winner.is_none() {
+                        winner = Some(object);
+                    } else if let Err(error) = give_back(object, owner) {
+                        cold_path();
+                        warn!(target: "ntsync", handle=object.as_raw_fd(); "Failed to give back an object acquired by an later chunk: {error}");
+                    }
+                },
+                Err(Error::Timeout) => failure = failure.or(Some(Error::Timeout)),
+                Err(error) => {
+                    if matches!(failure, None | Some(Error::Timeout)) {
+                        failure = Some(error);
                     }
-                    ids.push(mutex.id as u64);
                 },
             }
         }
-        let mut args = WaitArgs::new(
-            timeout.and_then(|st| st.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).ok()).unwrap_or(u64::MAX),
-            ids.as_ptr() as u64,
-            ids.len() as u32,
-            0,
-            flags.bits(),
-            owner.unwrap_or_default().0,
-            alertid as u32,
-        );
-        match unsafe { ntsync_wait_any(self.inner.handle.as_raw_fd(), raw!(mut args: WaitArgs)) } {
-            Ok(_) => {
+        match winner {
+            Some(object) => {
                 Ok(WaitAnyStatus {
-                    alerted: args.index == args.count,
-                    objects: return_sources,
-                    index: if args.index == args.count {
-                        0
-                    } else {
-                        args.count
-                    },
+                    alerted: false,
+                    index: objects.iter().position(|source| *source == object).unwrap_or_default() as u32,
+                    objects,
+                    alert: None,
                 })
             },
+            None => Err(failure.unwrap_or(Error::Timeout).with_context("wait_any")),
+        }
+    }
+
+    /// Waits until one of the `events` fires and returns it, or [None] if the wait timed out.
+    ///
+    /// Unlike [wait_any](NtSync::wait_any) the ids are taken directly from the slice, so no [HashSet] is built and the index of the kernel maps to the position in `events`.
+    /// An event can be contained more than once, the first position of an signaled event is returned then.
+    /// More than [MAX_WAIT_COUNT] events are rejected with [Error::InvalidValue] without asking the kernel.
+    /// The timeout is measured with the realtime clock.
+    pub fn wait_first_event(&self, events: &[Event], timeout: Option<SystemTime>) -> Result<Option<Event>> {
+        if events.is_empty() {
+            cold_path();
+            return Err(Error::EmptyWaitSet.with_context("wait_first_event"));
+        }
+        if events.len() > MAX_WAIT_COUNT {
+            cold_path();
+            debug!(target: "ntsync", "Can not wait on {} events, the kernel accepts at most {MAX_WAIT_COUNT}", events.len());
+            return Err(Error::InvalidValue.with_context("wait_first_event"));
+        }
+        let ids: Vec<u64> = events.iter().map(|event| event.id as u64).collect();
+        let mut args = WaitArgs::new(timeout_ns(timeout), ids.as_ptr() as u64, ids.len() as u32, 0, NtSyncFlags::WaitRealtime.bits(), 0, 0);
+        args.debug_check(&ids);
+        #[cfg(metrics)]
+        let _registration = self.inner.waits.register(&events.iter().map(|event| EventSources::Event(*event)).collect::<Vec<_>>(), None, false);
+        match unsafe { ntsync_wait_any(self.inner.handle.as_raw_fd(), raw!(mut args: WaitArgs)) } {
+            Ok(_) => {
+                args.debug_check(&ids);
+                Ok(events.get(args.index as usize).copied())
+            },
             Err(errno) => {
-                match errno {
-                    Errno::EINTR => Err(Error::Interrupt),
-                    Errno::EOWNERDEAD => Err(Error::OwnerDead),
-                    Errno::ETIMEDOUT => Err(Error::Timeout),
-                    Errno::EINVAL => Err(Error::InvalidValue),
-                    other => Err(Error::Unknown(other as i32)),
-                }
+                cold_path();
+                let error = match errno {
+                    Errno::ETIMEDOUT => return Ok(None),
+                    Errno::EINTR => Error::Interrupt,
+                    Errno::EBADF => device_gone(self),
+                    Errno::EINVAL => Error::InvalidValue,
+                    other => Error::Unknown(other as i32),
+                };
+                Err(error.with_context("wait_first_event"))
             },
         }
     }
 }
 
 //#define NTSYNC_IOC_WAIT_ANY             _IOWR('N', 0x82, struct ntsync_wait_args)
-ioctl_readwrite!(ntsync_wait_any, NTSYNC_MAGIC, 0x82, WaitArgs);
+ioctl_readwrite!(ntsync_wait_any, abi::MAGIC, abi::WAIT_ANY, WaitArgs);
 //#define NTSYNC_IOC_WAIT_ALL             _IOWR('N', 0x83, struct ntsync_wait_args)
-ioctl_readwrite!(ntsync_wait_all, NTSYNC_MAGIC, 0x83, WaitArgs);
+ioctl_readwrite!(ntsync_wait_all, abi::MAGIC, abi::WAIT_ALL, WaitArgs);
@@ -0,0 +1,50 @@
+use ntsync::abi::*;
+
+/// The request numbers of `include/uapi/linux/ntsync.h`, expanded by hand from the `_IOR`, `_IOW` and `_IOWR` macros.
+#[test]
+fn request_numbers() {
+    assert_eq!(MAGIC, b'N');
+    #[cfg(semaphore)]
+    {
+        assert_eq!(CREATE_SEM_REQUEST, 0x4008_4E80);
+        assert_eq!(SEM_READ_REQUEST, 0x8008_4E8B);
+    }
+    assert_eq!(SEM_RELEASE_REQUEST, 0xC004_4E81);
+    assert_eq!(WAIT_ANY_REQUEST, 0xC028_4E82);
+    assert_eq!(WAIT_ALL_REQUEST, 0xC028_4E83);
+    #[cfg(mutex)]
+    {
+        assert_eq!(CREATE_MUTEX_REQUEST, 0x4008_4E84);
+        assert_eq!(MUTEX_UNLOCK_REQUEST, 0xC008_4E85);
+        assert_eq!(MUTEX_READ_REQUEST, 0x8008_4E8C);
+    }
+    assert_eq!(MUTEX_KILL_REQUEST, 0x4004_4E86);
+    assert_eq!(CREATE_EVENT_REQUEST, 0x4008_4E87);
+    assert_eq!(EVENT_SET_REQUEST, 0x8004_4E88);
+    assert_eq!(EVENT_RESET_REQUEST, 0x8004_4E89);
+    assert_eq!(EVENT_PULSE_REQUEST, 0x8004_4E8A);
+    assert_eq!(EVENT_READ_REQUEST, 0x8008_4E8D);
+}
+
+#[test]
+fn request_code_layout() {
+    assert_eq!(request_code(READ | WRITE, MAGIC, WAIT_ANY, 40), WAIT_ANY_REQUEST);
+    assert_eq!(request_code(READ, MAGIC, EVENT_READ, 8) & 0xFF, u64::from(EVENT_READ));
+    assert_eq!((request_code(WRITE, MAGIC, CREATE_EVENT, 8) >> 8) & 0xFF, u64::from(MAGIC));
+}
+
+/// The `minimal` backend computes the request numbers itself, they have to match the ones the macros of nix compute.
+#[test]
+// The type of the request number differs between the libc targets.
+#[allow(clippy::unnecessary_cast)]
+fn request_code_matches_nix() {
+    for (number, size) in [
+        (EVENT_READ, 8),
+        (SEM_RELEASE, 4),
+        (WAIT_ANY, 40),
+    ] {
+        assert_eq!(request_code(READ, MAGIC, number, size), nix::request_code_read!(MAGIC, number, size) as u64);
+        assert_eq!(request_code(WRITE, MAGIC, number, size), nix::request_code_write!(MAGIC, number, size) as u64);
+        assert_eq!(request_code(READ | WRITE, MAGIC, number, size), nix::request_code_readwrite!(MAGIC, number, size) as u64);
+    }
+}
@@ -0,0 +1,47 @@
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+};
+use rstest::rstest;
+use std::os::fd::{
+    AsRawFd,
+    BorrowedFd,
+    OwnedFd,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+/// Duplicates the file descriptor, like it happens when it is sent to another process.
+fn duplicate(fd: &impl AsRawFd) -> Result<OwnedFd, Error> {
+    unsafe { BorrowedFd::borrow_raw(fd.as_raw_fd()) }.try_clone_to_owned().map_err(Error::IOError)
+}
+
+#[test(rstest)]
+fn adopt_event(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, true)?;
+    let adopted = instance.adopt_event(duplicate(&event)?)?;
+    event.signal()?;
+    assert!(adopted.status()?.signaled(), "The adopted event does not share the state");
+    adopted.delete()?;
+    event.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(semaphore)]
+fn adopt_event_as_semaphore(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let result = instance.adopt_semaphore(duplicate(&event)?);
+    assert_eq!(
+        result.map(|_| ()),
+        Err(Error::WrongObjectType {
+            expected: "semaphore"
+        }),
+        "An event was adopted as semaphore"
+    );
+    event.delete()?;
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+};
+use rstest::rstest;
+use std::{
+    collections::HashSet,
+    time::{
+        Duration,
+        SystemTime,
+    },
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn reset_alert_after_wait(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let alert = instance.new_event(false, true)?;
+    alert.signal()?;
+    let status = instance.wait_any(HashSet::from([event.into()]), None, None, NtSyncFlags::WaitRealtime, Some(alert))?;
+    assert!(status.alerted, "The signaled alert did not stop the wait");
+    assert!(status.reset_alert()?, "The alert was not reset");
+    assert!(!alert.status()?.signaled(), "The alert is still signaled");
+
+    let timeout = SystemTime::now() + Duration::from_millis(50);
+    let result = instance.wait_any(HashSet::from([event.into()]), Some(timeout), None, NtSyncFlags::WaitRealtime, Some(alert));
+    assert_eq!(result.map(|status| status.alerted), Err(Error::Timeout), "The second wait did not block");
+    event.delete()?;
+    alert.delete()?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+};
+use rstest::rstest;
+use std::{
+    collections::HashSet,
+    time::UNIX_EPOCH,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn wait_with_deleted_alert(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let alert = instance.new_event(false, true)?;
+    alert.delete()?;
+    let result = instance.wait_any(HashSet::from([event.into()]), Some(UNIX_EPOCH), None, NtSyncFlags::WaitRealtime, Some(alert));
+    assert_eq!(result.map(|_| ()), Err(Error::AlertInvalid), "The deleted alert was not detected");
+    let result = instance.wait_all(HashSet::from([event.into()]), Some(UNIX_EPOCH), None, NtSyncFlags::WaitRealtime, Some(alert));
+    assert_eq!(result.map(|_| ()), Err(Error::AlertInvalid), "The deleted alert was not detected");
+    event.delete()
+}
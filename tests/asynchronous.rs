@@ -0,0 +1,71 @@
+#![cfg(asynchronous)]
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+};
+use rstest::rstest;
+use std::{
+    collections::HashSet,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+use test_log::test;
+use tokio::{
+    io::{
+        Interest,
+        unix::AsyncFd,
+    },
+    runtime,
+    time,
+};
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn readiness_eventfd(instance: NtSync) -> Result<(), Error> {
+    let runtime = runtime::Builder::new_current_thread().enable_io().build().map_err(Error::IOError)?;
+    let event = instance.new_event(false, false)?;
+    let fd = event.readiness_eventfd(&instance)?;
+    runtime.block_on(async {
+        let fd = AsyncFd::with_interest(fd, Interest::READABLE).map_err(Error::IOError)?;
+        event.signal()?;
+        let guard = fd.readable().await.map_err(Error::IOError)?;
+        assert!(guard.ready().is_readable(), "The eventfd is not readable after the signal");
+        Ok::<(), Error>(())
+    })?;
+    assert!(!event.status()?.signaled(), "The helper did not consume the signal");
+    event.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+fn async_wait_timeout(instance: NtSync) -> Result<(), Error> {
+    let runtime = runtime::Builder::new_current_thread().enable_time().build().map_err(Error::IOError)?;
+    let event = instance.new_event(false, false)?;
+    let start = Instant::now();
+    let wait = instance.wait_any_async(HashSet::from([event.into()]), None, NtSyncFlags::empty())?;
+    let result = runtime.block_on(time::timeout(Duration::from_millis(50), wait));
+    let elapsed = start.elapsed();
+    assert!(result.is_err(), "The wait on an unsignaled event completed: {result:?}");
+    assert!(elapsed < Duration::from_secs(2), "The helper thread was released after {elapsed:?}");
+    event.signal()?;
+    assert!(event.status()?.signaled(), "The cancelled helper thread still consumed the signal");
+    event.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+fn async_wait_completes(instance: NtSync) -> Result<(), Error> {
+    let runtime = runtime::Builder::new_current_thread().enable_time().build().map_err(Error::IOError)?;
+    let event = instance.new_event(true, false)?;
+    let wait = instance.wait_any_async(HashSet::from([event.into()]), None, NtSyncFlags::empty())?;
+    let status = runtime.block_on(time::timeout(Duration::from_secs(5), wait)).map_err(|error| Error::IOError(error.into()))??;
+    assert!(!status.alerted, "The wait was stopped by its internal alert");
+    event.delete()?;
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use ntsync::Backoff;
+use std::time::Duration;
+use test_log::test;
+
+#[test]
+fn grows_to_cap() {
+    let backoff = Backoff {
+        initial: Duration::from_millis(1),
+        max: Duration::from_millis(20),
+        factor: 2,
+    };
+    let delays: Vec<Duration> = backoff.delays().take(8).collect();
+    assert_eq!(
+        delays[..5],
+        [
+            1, 2, 4, 8, 16
+        ]
+        .map(Duration::from_millis),
+        "The delays did not grow exponentially"
+    );
+    assert!(delays[5..].iter().all(|delay| *delay == backoff.max), "The delays were not capped: {delays:?}");
+}
+
+#[test]
+fn fixed_interval() {
+    let interval = Duration::from_millis(5);
+    assert!(Backoff::from(interval).delays().take(4).all(|delay| delay == interval), "The fixed backoff changed its delay");
+}
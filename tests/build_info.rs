@@ -0,0 +1,32 @@
+use ntsync::{
+    BuildInfo,
+    build_info,
+};
+use std::fs;
+
+#[test]
+fn features_of_the_build() {
+    let info = build_info();
+    assert_eq!(info.version, env!("CARGO_PKG_VERSION"), "The version is wrong");
+    assert_eq!(info.target_os, std::env::consts::OS, "The target is wrong");
+    assert_eq!(info.has_feature("semaphore"), cfg!(feature = "semaphore"), "The semaphore feature is reported wrong");
+    assert_eq!(info.has_feature("mutex"), cfg!(feature = "mutex"), "The mutex feature is reported wrong");
+    assert_eq!(info.has_feature("metrics"), cfg!(feature = "metrics"), "The metrics feature is reported wrong");
+    assert_eq!(info.has_feature("nix"), cfg!(feature = "nix"), "The nix feature is reported wrong");
+    assert!(info.features.is_sorted(), "The features are not sorted");
+}
+
+#[test]
+fn every_feature_is_known() {
+    let manifest = fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")).expect("The manifest can not be read");
+    let mut features: Vec<&str> = manifest
+        .lines()
+        .skip_while(|line| line.trim() != "[features]")
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+        .filter_map(|line| line.split_once('=').map(|(name, _)| name.trim()))
+        .filter(|name| *name != "default")
+        .collect();
+    features.sort_unstable();
+    assert_eq!(BuildInfo::known_features(), features, "build_info does not list the features of Cargo.toml");
+}
@@ -20,7 +20,7 @@ use fixtures::*;
 macro_rules! test_op {
     ($object_op:expr, $op_name:literal) => {
         match $object_op {
-            Err(Error::AlreadyClosed) => {},
+            Err(error) if matches!(error.kind(), Error::AlreadyClosed) => {},
             Err(other) => {
                 error!("{} returned {:?} not {:?}", $op_name, other, Error::AlreadyClosed);
                 return Err(other);
@@ -38,7 +38,7 @@ fn common_tests<T: NTSyncObjects>(object: T, instance: NtSync) -> Result<(), Err
         instance.wait_any(hash!(object.into()), Some(SystemTime::now() + Duration::from_millis(200)), Some(OwnerId::random()), NtSyncFlags::default(), None);
     match result {
         Ok(_) => return Err(Error::InvalidValue),
-        Err(Error::InvalidValue) => {},
+        Err(error) if matches!(error.kind(), Error::InvalidValue) => {},
         Err(error) => {
             info!("got unexpected error on after trying to wait on object: {error}");
             return Err(error);
@@ -86,3 +86,44 @@ fn delete_semaphore(instance: NtSync) -> Result<(), Error> {
     common_tests(object, instance)?;
     Ok(())
 }
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn delete_detailed_locked_mutex(instance: NtSync) -> Result<(), Error> {
+    let owner = OwnerId::random();
+    let object = instance.new_mutex()?;
+    instance.wait_all(hash!(object.into()), None, Some(owner), NtSyncFlags::empty(), None)?;
+    let report = object.delete_detailed()?;
+    match report.status {
+        Some(status) => assert_eq!(status.owner(), Some(owner), "Report does not show the mutex as held"),
+        None => panic!("Report does not contain the status"),
+    }
+    test_op!(object.read(), "object.read");
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(all(mutex, semaphore))]
+fn delete_all_mixed(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let semaphore = instance.new_semaphore(1)?;
+    let mutex = instance.new_mutex()?;
+    let closed = instance.new_event(false, false)?;
+    closed.delete()?;
+    let results = instance.delete_all(vec![
+        event.into(),
+        semaphore.into(),
+        closed.into(),
+        mutex.into(),
+        event.into(),
+    ]);
+    assert_eq!(results.len(), 4, "The duplicate event was not removed");
+    for (object, result) in results {
+        if object == closed.into() {
+            assert_eq!(result, Err(Error::AlreadyClosed), "The closed event was deleted again");
+        } else {
+            assert_eq!(result, Ok(()), "{object:?} was not deleted");
+        }
+    }
+    Ok(())
+}
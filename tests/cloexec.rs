@@ -0,0 +1,46 @@
+use nix::libc;
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+};
+use rstest::rstest;
+use std::os::fd::AsRawFd as _;
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+fn close_on_exec(fd: i32) -> bool {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    assert!(flags >= 0, "Failed to read the flags of the file descriptor");
+    flags & libc::FD_CLOEXEC != 0
+}
+
+#[test(rstest)]
+fn objects_close_on_exec(instance: NtSync) -> Result<(), Error> {
+    assert!(close_on_exec(instance.as_raw_fd()), "The device is inherited");
+    let event = instance.new_event(false, false)?;
+    assert!(close_on_exec(event.as_raw_fd()), "The event is inherited");
+    #[cfg(semaphore)]
+    {
+        let semaphore = instance.new_semaphore(1)?;
+        assert!(close_on_exec(semaphore.as_raw_fd()), "The semaphore is inherited");
+        semaphore.delete()?;
+    }
+    #[cfg(mutex)]
+    {
+        let mutex = instance.new_mutex()?;
+        assert!(close_on_exec(mutex.as_raw_fd()), "The mutex is inherited");
+        mutex.delete()?;
+    }
+    event.delete()
+}
+
+#[test(rstest)]
+fn inheritable_event(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event_inheritable(true, false)?;
+    assert!(!close_on_exec(event.as_raw_fd()), "The event is closed in an exec");
+    assert!(event.status()?.signaled());
+    event.delete()
+}
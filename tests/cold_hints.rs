@@ -0,0 +1,20 @@
+#![cfg(no_cold_hints)]
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+};
+use rstest::rstest;
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+/// The error paths call the helper that lost its `#[cold]` attribute, so they are exercised once.
+#[test(rstest)]
+fn error_paths_without_cold_hints(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    event.delete()?;
+    assert_eq!(event.delete(), Err(Error::AlreadyClosed), "The double delete was not detected");
+    Ok(())
+}
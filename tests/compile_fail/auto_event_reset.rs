@@ -0,0 +1,10 @@
+use ntsync::{
+    Error,
+    NtSync,
+};
+
+fn main() -> Result<(), Error> {
+    let instance = NtSync::new()?;
+    instance.new_auto_event(true)?.reset()?;
+    Ok(())
+}
@@ -0,0 +1,59 @@
+#![cfg(all(mutex, semaphore))]
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    OwnerId,
+};
+use rstest::rstest;
+use std::{
+    sync::{
+        Arc,
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+    },
+    thread::{
+        Builder,
+        sleep,
+    },
+    time::Duration,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn notify_one_wakes_waiter(instance: NtSync) -> Result<(), Error> {
+    let (notifier, waiter) = (OwnerId::new(1), OwnerId::new(2));
+    let mutex = instance.new_mutex()?;
+    let condvar = Arc::new(instance.new_condvar()?);
+    let ready = Arc::new(AtomicBool::new(false));
+    let thread_data = (instance.clone(), Arc::clone(&condvar), Arc::clone(&ready));
+    let thread = Builder::new()
+        .name("condvar waiter".to_owned())
+        .spawn(move || -> Result<(), Error> {
+            let (instance, condvar, ready) = thread_data;
+            let mut guard = instance.lock_mutex(mutex, waiter, None)?;
+            while !ready.load(Ordering::Acquire) {
+                guard = condvar.wait(guard)?;
+            }
+            assert_eq!(mutex.read()?.owner(), Some(waiter), "The waiter did not lock the mutex again");
+            Ok(())
+        })
+        .map_err(Error::IOError)?;
+    sleep(Duration::from_millis(50));
+    let guard = instance.lock_mutex(mutex, notifier, None)?;
+    ready.store(true, Ordering::Release);
+    condvar.notify_one()?;
+    drop(guard);
+    match thread.join() {
+        Ok(result) => result?,
+        Err(error) => panic!("The waiter panicked: {error:?}"),
+    }
+    assert_eq!(mutex.read()?.owner(), None, "The guard of the waiter did not unlock the mutex");
+    mutex.delete()?;
+    Ok(())
+}
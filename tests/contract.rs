@@ -0,0 +1,334 @@
+//! Generates random sequences of operations and compares the results with an model of the kernel objects.
+//!
+//! The harness found two discrepancies which are fixed now:
+//! - `NtSync::new_event` passed the signaled and manual flag in the wrong order to the kernel.
+//! - `WaitAnyStatus::index` returned the number of objects instead of the index of the triggered object.
+#![cfg(all(mutex, semaphore))]
+use ntsync::{
+    Error,
+    Event,
+    EventSources,
+    Mutex,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    OwnerId,
+    Semaphore,
+};
+use proptest::prelude::*;
+use std::{
+    collections::HashSet,
+    time::UNIX_EPOCH,
+};
+
+const EVENTS: usize = 2;
+const SEMAPHORE: usize = EVENTS;
+const MUTEX: usize = EVENTS + 1;
+const OBJECTS: usize = EVENTS + 2;
+const MAXIMUM: u32 = 3;
+
+#[derive(Debug, Clone)]
+enum Operation {
+    Signal(usize),
+    Reset(usize),
+    Pulse(usize),
+    Release(u32),
+    Unlock(u32),
+    WaitAny {
+        mask: u8,
+        owner: u32,
+    },
+    WaitAll {
+        mask: u8,
+        owner: u32,
+    },
+    Read(usize),
+    Delete(usize),
+}
+
+fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+        (0..EVENTS).prop_map(Operation::Signal),
+        (0..EVENTS).prop_map(Operation::Reset),
+        (0..EVENTS).prop_map(Operation::Pulse),
+        (1..=2u32).prop_map(Operation::Release),
+        (1..=2u32).prop_map(Operation::Unlock),
+        (1..16u8, 1..=2u32).prop_map(|(mask, owner)| {
+            Operation::WaitAny {
+                mask,
+                owner,
+            }
+        }),
+        (1..16u8, 1..=2u32).prop_map(|(mask, owner)| {
+            Operation::WaitAll {
+                mask,
+                owner,
+            }
+        }),
+        (0..OBJECTS).prop_map(Operation::Read),
+        (0..OBJECTS).prop_map(Operation::Delete),
+    ]
+}
+
+/// The expected state of the objects
+#[derive(Debug)]
+struct Model {
+    signaled: [bool; EVENTS],
+    manual: [bool; EVENTS],
+    count: u32,
+    owner: u32,
+    depth: u32,
+    deleted: [bool; OBJECTS],
+}
+
+impl Model {
+    fn ready(&self, index: usize, owner: u32) -> bool {
+        match index {
+            SEMAPHORE => self.count != 0,
+            MUTEX => self.owner == 0 || self.owner == owner,
+            event => self.signaled[event],
+        }
+    }
+
+    fn consume(&mut self, index: usize, owner: u32) {
+        match index {
+            SEMAPHORE => self.count -= 1,
+            MUTEX => {
+                self.owner = owner;
+                self.depth += 1;
+            },
+            event => {
+                if !self.manual[event] {
+                    self.signaled[event] = false;
+                }
+            },
+        }
+    }
+}
+
+struct Objects {
+    events: [Event; EVENTS],
+    semaphore: Semaphore,
+    mutex: Mutex,
+}
+
+impl Objects {
+    fn source(&self, index: usize) -> EventSources {
+        match index {
+            SEMAPHORE => self.semaphore.into(),
+            MUTEX => self.mutex.into(),
+            event => self.events[event].into(),
+        }
+    }
+
+    fn index(&self, source: &EventSources) -> usize {
+        (0..OBJECTS).find(|index| self.source(*index) == *source).unwrap_or(OBJECTS)
+    }
+
+    fn delete(&self, index: usize) -> Result<(), Error> {
+        match index {
+            SEMAPHORE => self.semaphore.delete(),
+            MUTEX => self.mutex.delete(),
+            event => self.events[event].delete(),
+        }
+    }
+}
+
+fn known(error: &Error) -> bool {
+    !matches!(error, Error::Unknown(_) | Error::IOError(_))
+}
+
+fn fail(error: Error) -> TestCaseError {
+    TestCaseError::fail(format!("Unexpected error: {error}"))
+}
+
+fn selection(mask: u8) -> Vec<usize> {
+    (0..OBJECTS).filter(|index| mask & (1 << index) != 0).collect()
+}
+
+#[allow(clippy::too_many_lines)]
+fn run(operations: Vec<Operation>) -> Result<(), TestCaseError> {
+    let instance = NtSync::new().map_err(fail)?;
+    let objects = Objects {
+        events: [
+            instance.new_event(false, false).map_err(fail)?,
+            instance.new_event(false, true).map_err(fail)?,
+        ],
+        semaphore: instance.new_semaphore(MAXIMUM).map_err(fail)?,
+        mutex: instance.new_mutex().map_err(fail)?,
+    };
+    let mut model = Model {
+        signaled: [false; EVENTS],
+        manual: [
+            false, true,
+        ],
+        count: MAXIMUM,
+        owner: 0,
+        depth: 0,
+        deleted: [false; OBJECTS],
+    };
+
+    for operation in operations {
+        match operation {
+            Operation::Signal(event) | Operation::Reset(event) | Operation::Pulse(event) => {
+                let result = match operation {
+                    Operation::Signal(_) => objects.events[event].signal(),
+                    Operation::Reset(_) => objects.events[event].reset(),
+                    _ => objects.events[event].pulse(),
+                };
+                if model.deleted[event] {
+                    prop_assert_eq!(result, Err(Error::AlreadyClosed));
+                    continue;
+                }
+                prop_assert_eq!(result, Ok(model.signaled[event]), "{:?} returned the wrong previous state", operation);
+                model.signaled[event] = matches!(operation, Operation::Signal(_));
+            },
+            Operation::Release(amount) => {
+                let result = objects.semaphore.release(amount);
+                if model.deleted[SEMAPHORE] {
+                    prop_assert_eq!(result, Err(Error::AlreadyClosed));
+                } else if model.count + amount > MAXIMUM {
+                    prop_assert_eq!(result, Err(Error::SemaphoreOverflow));
+                } else {
+                    prop_assert_eq!(result, Ok(model.count));
+                    model.count += amount;
+                }
+            },
+            Operation::Unlock(owner) => {
+                let result = objects.mutex.unlock(OwnerId::new(owner));
+                if model.deleted[MUTEX] {
+                    prop_assert_eq!(result, Err(Error::AlreadyClosed));
+                } else if model.owner != owner {
+                    prop_assert_eq!(
+                        result,
+                        Err(Error::WrongOwner {
+                            expected: OwnerId::new(owner),
+                            actual: (model.owner != 0).then(|| OwnerId::new(model.owner)),
+                        })
+                    );
+                } else {
+                    prop_assert_eq!(result, Ok(()));
+                    model.depth -= 1;
+                    if model.depth == 0 {
+                        model.owner = 0;
+                    }
+                }
+            },
+            Operation::WaitAny {
+                mask,
+                owner,
+            } => {
+                let selected = selection(mask);
+                let sources: HashSet<EventSources> = selected.iter().map(|index| objects.source(*index)).collect();
+                let result = instance.wait_any(sources, Some(UNIX_EPOCH), Some(OwnerId::new(owner)), NtSyncFlags::empty(), None);
+                if selected.iter().any(|index| model.deleted[*index]) {
+                    prop_assert_eq!(result.map(|_| ()), Err(Error::InvalidValue));
+                    continue;
+                }
+                match result {
+                    Ok(status) => {
+                        prop_assert!(!status.alerted);
+                        prop_assert_eq!(status.objects().len(), selected.len());
+                        let expected = status.objects().iter().map(|source| objects.index(source)).find(|index| model.ready(*index, owner));
+                        let triggered = status.objects().get(status.index as usize).map(|source| objects.index(source));
+                        prop_assert_eq!(triggered, expected, "wait_any returned the wrong index");
+                        if let Some(index) = expected {
+                            model.consume(index, owner);
+                        }
+                    },
+                    Err(error) if matches!(error.kind(), Error::Timeout) => {
+                        prop_assert!(!selected.iter().any(|index| model.ready(*index, owner)), "wait_any timed out on an ready object");
+                    },
+                    Err(error) => prop_assert!(false, "wait_any failed with {}", error),
+                }
+            },
+            Operation::WaitAll {
+                mask,
+                owner,
+            } => {
+                let selected = selection(mask);
+                let sources: HashSet<EventSources> = selected.iter().map(|index| objects.source(*index)).collect();
+                let result = instance.wait_all(sources, Some(UNIX_EPOCH), Some(OwnerId::new(owner)), NtSyncFlags::empty(), None);
+                if selected.iter().any(|index| model.deleted[*index]) {
+                    prop_assert_eq!(result.map(|_| ()), Err(Error::InvalidValue));
+                    continue;
+                }
+                let ready = selected.iter().all(|index| model.ready(*index, owner));
+                match result {
+                    Ok(status) => {
+                        prop_assert!(ready, "wait_all succeeded although not all objects were ready");
+                        prop_assert_eq!(status.objects().len(), selected.len());
+                        for index in selected {
+                            model.consume(index, owner);
+                        }
+                    },
+                    Err(error) if matches!(error.kind(), Error::Timeout) => prop_assert!(!ready, "wait_all timed out although all objects were ready"),
+                    Err(error) => prop_assert!(false, "wait_all failed with {}", error),
+                }
+            },
+            Operation::Read(index) => {
+                let deleted = model.deleted[index];
+                match index {
+                    SEMAPHORE => {
+                        match objects.semaphore.read() {
+                            Ok(status) => {
+                                prop_assert!(!deleted);
+                                prop_assert!(status.count <= status.max(), "Semaphore count exceeds the maximum");
+                                prop_assert_eq!(status.count, model.count);
+                                prop_assert_eq!(status.max(), MAXIMUM);
+                            },
+                            Err(error) => prop_assert!(deleted && error == Error::AlreadyClosed, "read failed with {}", error),
+                        }
+                    },
+                    MUTEX => {
+                        match objects.mutex.read() {
+                            Ok(status) => {
+                                prop_assert!(!deleted);
+                                prop_assert_eq!(status.owner(), (model.owner != 0).then(|| OwnerId::new(model.owner)));
+                                prop_assert_eq!(status.depth(), (model.depth != 0).then_some(model.depth));
+                            },
+                            Err(error) => prop_assert!(deleted && error == Error::AlreadyClosed, "read failed with {}", error),
+                        }
+                    },
+                    event => {
+                        match objects.events[event].read() {
+                            Ok(status) => {
+                                prop_assert!(!deleted);
+                                prop_assert_eq!(status.signaled(), model.signaled[event]);
+                                prop_assert_eq!(status.manual_reset(), model.manual[event]);
+                            },
+                            Err(error) => prop_assert!(deleted && error == Error::AlreadyClosed, "read failed with {}", error),
+                        }
+                    },
+                }
+            },
+            Operation::Delete(index) => {
+                let result = objects.delete(index);
+                if model.deleted[index] {
+                    prop_assert_eq!(result, Err(Error::AlreadyClosed));
+                } else {
+                    prop_assert_eq!(result, Ok(()));
+                    model.deleted[index] = true;
+                }
+            },
+        }
+    }
+
+    for index in 0..OBJECTS {
+        if !model.deleted[index] {
+            let result = objects.delete(index);
+            if let Err(error) = &result {
+                prop_assert!(known(error), "delete failed with {}", error);
+            }
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+    #[test]
+    fn contract(operations in prop::collection::vec(operation(), 1..40)) {
+        run(operations)?;
+    }
+}
@@ -0,0 +1,30 @@
+#![cfg(debug_event)]
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+};
+use rstest::rstest;
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn double_signal_warns(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    assert!(!event.signal_tracked()?, "The new event was already signaled");
+    assert_eq!(event.lost_signal_warnings(), 0, "The first signal was reported as lost");
+    assert!(event.signal_tracked()?, "The second signal did not see the first one");
+    assert_eq!(event.lost_signal_warnings(), 1, "The lost signal was not reported");
+    event.delete()
+}
+
+#[test(rstest)]
+fn manual_double_signal_is_quiet(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, true)?;
+    event.signal_tracked()?;
+    event.signal_tracked()?;
+    assert_eq!(event.lost_signal_warnings(), 0, "An manual reset event can not lose signals");
+    event.delete()
+}
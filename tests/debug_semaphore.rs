@@ -0,0 +1,26 @@
+#![cfg(debug_semaphore)]
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+};
+use rstest::rstest;
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn double_release(instance: NtSync) -> Result<(), Error> {
+    let semaphore = instance.new_semaphore(2)?;
+    instance.wait_any(hash!(semaphore.into()), None, None, NtSyncFlags::empty(), None)?;
+    semaphore.release(1)?;
+    assert_eq!(semaphore.accounting_warnings(), 0, "An balanced release was reported");
+    assert_eq!(semaphore.release(1), Err(Error::SemaphoreOverflow));
+    assert_eq!(semaphore.accounting_warnings(), 1, "The double release was not reported");
+    semaphore.read()?;
+    assert_eq!(semaphore.accounting_warnings(), 1, "The count diverged after the double release");
+    semaphore.delete()?;
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+};
+use rstest::rstest;
+use std::{
+    collections::HashSet,
+    mem,
+    os::fd::AsRawFd as _,
+    time::UNIX_EPOCH,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn wait_after_device_closed(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(true, true)?;
+    assert_eq!(unsafe { nix::libc::close(instance.as_raw_fd()) }, 0, "Failed to close the device");
+    let result = instance.wait_any(HashSet::from([event.into()]), Some(UNIX_EPOCH), None, NtSyncFlags::WaitRealtime, None);
+    // The device is already closed, so the instance must not close it again.
+    mem::forget(instance);
+    assert_eq!(result.map(|_| ()), Err(Error::DeviceGone), "The closed device was not detected");
+    event.delete()
+}
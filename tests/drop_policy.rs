@@ -0,0 +1,32 @@
+use ntsync::{
+    DropPolicy,
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+};
+use rstest::rstest;
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+fn fail_drop(instance: &NtSync) -> Result<(), Error> {
+    let shared = instance.new_shared_event(false, false)?;
+    let event = shared.event();
+    event.delete()?;
+    drop(shared);
+    Ok(())
+}
+
+#[test(rstest)]
+fn drop_policy(instance: NtSync) -> Result<(), Error> {
+    assert_eq!(instance.drop_policy(), DropPolicy::LogErrors);
+    fail_drop(&instance)?;
+    assert_eq!(instance.logged_drop_failures(), 1);
+
+    instance.set_drop_policy(DropPolicy::Silent);
+    assert_eq!(instance.drop_policy(), DropPolicy::Silent);
+    fail_drop(&instance)?;
+    assert_eq!(instance.logged_drop_failures(), 1);
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+};
+use rstest::rstest;
+use std::{
+    collections::HashSet,
+    time::UNIX_EPOCH,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn wait_error_context(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let Err(error) = instance.wait_all(HashSet::from([event.into()]), Some(UNIX_EPOCH), None, NtSyncFlags::WaitRealtime, None) else {
+        panic!("The wait on an unsignaled event did not time out");
+    };
+    assert_eq!(error.operation(), Some("wait_all"));
+    assert_eq!(error, Error::Timeout);
+    assert!(matches!(error.kind(), Error::Timeout));
+    assert_eq!(error.to_string(), format!("wait_all: {}", Error::Timeout));
+
+    let Err(error) = instance.wait_any(HashSet::new(), None, None, NtSyncFlags::empty(), None) else {
+        panic!("The empty wait was not rejected");
+    };
+    assert_eq!(error.operation(), Some("wait_any"));
+    assert_eq!(error.into_kind(), Error::EmptyWaitSet);
+    event.delete()
+}
+
+#[test(rstest)]
+fn signal_error_context(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    event.delete()?;
+    let Err(error) = event.signal() else {
+        panic!("The deleted event was signaled");
+    };
+    assert_eq!(error.operation(), Some("signal"));
+    assert_eq!(error, Error::AlreadyClosed);
+    Ok(())
+}
+
+#[test]
+fn context_is_kept() {
+    let error = Error::Timeout.with_context("wait_any").with_context("park");
+    assert_eq!(error.operation(), Some("wait_any"));
+    assert_eq!(error, Error::Timeout.with_context("park"));
+    assert_ne!(error, Error::Interrupt);
+    assert_eq!(Error::Interrupt.operation(), None);
+}
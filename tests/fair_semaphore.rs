@@ -0,0 +1,133 @@
+use log::*;
+use ntsync::{
+    Error,
+    NtSync,
+};
+use rstest::rstest;
+use std::{
+    sync::Mutex,
+    thread::{
+        Builder,
+        scope,
+        sleep,
+    },
+    time::Duration,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+#[cfg(semaphore)]
+fn fair_arrival_order(instance: NtSync) -> Result<(), Error> {
+    let semaphore = instance.new_fair_semaphore(1)?;
+    let order = Mutex::new(Vec::new());
+    semaphore.acquire(None)?;
+    scope(|scope| -> Result<(), Error> {
+        let mut threads = Vec::new();
+        for id in 0..3 {
+            let (semaphore, order) = (&semaphore, &order);
+            let thread = Builder::new()
+                .name(format!("waiter {id}"))
+                .spawn_scoped(scope, move || -> Result<(), Error> {
+                    semaphore.acquire(None)?;
+                    trace!("waiter {id} got the permit");
+                    if let Ok(mut order) = order.lock() {
+                        order.push(id);
+                    }
+                    semaphore.release(1)
+                })
+                .map_err(Error::IOError)?;
+            while semaphore.waiters() <= id && !thread.is_finished() {
+                sleep(Duration::from_millis(1));
+            }
+            threads.push(thread);
+        }
+        semaphore.release(1)?;
+        for thread in threads {
+            match thread.join() {
+                Ok(result) => result?,
+                Err(error) => panic!("Waiter panicked: {error:?}"),
+            }
+        }
+        Ok(())
+    })?;
+    match order.lock() {
+        Ok(order) => {
+            assert_eq!(
+                *order,
+                vec![
+                    0, 1, 2
+                ],
+                "Waiters were not served in arrival order"
+            )
+        },
+        Err(error) => panic!("Order was poisoned: {error}"),
+    }
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(semaphore)]
+fn priority_order(instance: NtSync) -> Result<(), Error> {
+    let semaphore = instance.new_fair_semaphore(1)?;
+    let order = Mutex::new(Vec::new());
+    semaphore.acquire(None)?;
+    scope(|scope| -> Result<(), Error> {
+        let mut threads = Vec::new();
+        for (id, priority) in [
+            1, 5, 3,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let (semaphore, order) = (&semaphore, &order);
+            let thread = Builder::new()
+                .name(format!("waiter {id}"))
+                .spawn_scoped(scope, move || -> Result<(), Error> {
+                    semaphore.acquire_with_priority(priority, None)?;
+                    trace!("waiter with priority {priority} got the permit");
+                    if let Ok(mut order) = order.lock() {
+                        order.push(priority);
+                    }
+                    Ok(())
+                })
+                .map_err(Error::IOError)?;
+            while semaphore.waiters() <= id && !thread.is_finished() {
+                sleep(Duration::from_millis(1));
+            }
+            threads.push(thread);
+        }
+        // Each release wakes exactly one waiter, so the order is not decided by the scheduler.
+        for served in 1..=3 {
+            semaphore.release(1)?;
+            while order.lock().map_or(0, |order| order.len()) < served {
+                sleep(Duration::from_millis(1));
+            }
+            if served == 1 {
+                assert_eq!(order.lock().map(|order| order.clone()).ok(), Some(vec![5]), "The single release did not wake the highest priority");
+            }
+        }
+        for thread in threads {
+            match thread.join() {
+                Ok(result) => result?,
+                Err(error) => panic!("Waiter panicked: {error:?}"),
+            }
+        }
+        Ok(())
+    })?;
+    match order.lock() {
+        Ok(order) => {
+            assert_eq!(
+                *order,
+                [
+                    5, 3, 1
+                ],
+                "Waiters were not served by priority"
+            )
+        },
+        Err(error) => panic!("Order was poisoned: {error}"),
+    }
+    Ok(())
+}
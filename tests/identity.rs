@@ -0,0 +1,27 @@
+use ntsync::{
+    Error,
+    Event,
+    EventSources,
+    NtSync,
+};
+use rstest::rstest;
+use std::os::fd::{
+    AsRawFd as _,
+    FromRawFd as _,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn identity_of_duplicate(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let fd = unsafe { nix::libc::dup(event.as_raw_fd()) };
+    assert_ne!(fd, -1, "Failed to duplicate the file descriptor");
+    let duplicate = unsafe { Event::from_raw_fd(fd) };
+    let original = EventSources::from(event).identity()?;
+    let copy = EventSources::from(duplicate).identity()?;
+    assert_eq!(original, copy, "Duplicated file descriptor has an different identity");
+    Ok(())
+}
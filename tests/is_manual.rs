@@ -0,0 +1,39 @@
+#![cfg(record)]
+//! Uses the global recording as counter of the ioctls, so it needs its own test binary.
+use ntsync::{
+    Error,
+    Event,
+    NTSyncObjects as _,
+    NtSync,
+    Operation,
+};
+use rstest::rstest;
+use std::os::fd::{
+    AsRawFd as _,
+    FromRawFd as _,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn is_manual_is_cached(instance: NtSync) -> Result<(), Error> {
+    let manual = instance.new_event(false, true)?;
+    let auto = instance.event_builder().build()?;
+    instance.start_recording();
+    assert!(manual.is_manual()?);
+    assert!(!auto.is_manual()?);
+    let trace = instance.stop_recording().unwrap_or_default();
+    assert!(trace.entries.is_empty(), "is_manual asked the kernel about an created event: {trace:?}");
+
+    // An wrapped file descriptor does not know its type, so it has to be read.
+    let wrapped = unsafe { Event::from_raw_fd(manual.as_raw_fd()) };
+    assert_eq!(wrapped, manual, "The wrapped event is not equal to the created one");
+    instance.start_recording();
+    assert!(wrapped.is_manual()?);
+    let trace = instance.stop_recording().unwrap_or_default();
+    assert!(matches!(trace.entries.as_slice(), [entry] if matches!(entry.operation, Operation::ReadEvent { .. })), "The type was not read: {trace:?}");
+    manual.delete()?;
+    auto.delete()
+}
@@ -0,0 +1,49 @@
+#![cfg(random)]
+use ntsync::{
+    Error,
+    JitteredTimeout,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+};
+use rstest::rstest;
+use std::{
+    collections::HashSet,
+    time::{
+        Duration,
+        Instant,
+    },
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test]
+fn samples_in_band() {
+    let timeout = JitteredTimeout::new(Duration::from_millis(100), 20);
+    let samples: Vec<Duration> = (0..1000).map(|_| timeout.sample()).collect();
+    for sample in &samples {
+        assert!((Duration::from_millis(80)..=Duration::from_millis(120)).contains(sample), "{sample:?} is outside of the band");
+    }
+    let unique: HashSet<&Duration> = samples.iter().collect();
+    assert!(unique.len() > 1, "The timeout was not jittered");
+}
+
+#[test]
+fn percent_is_clamped() {
+    let timeout = JitteredTimeout::new(Duration::from_millis(10), 250);
+    assert_eq!(timeout.percent(), 100);
+    assert!((0..1000).all(|_| timeout.sample() <= Duration::from_millis(20)));
+    assert_eq!(JitteredTimeout::new(Duration::from_millis(10), 0).sample(), Duration::from_millis(10));
+}
+
+#[test(rstest)]
+fn jittered_wait(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let start = Instant::now();
+    let result = instance.wait_any_with(HashSet::from([event.into()]), JitteredTimeout::new(Duration::from_millis(100), 50), None, NtSyncFlags::empty(), None);
+    assert_eq!(result.err(), Some(Error::Timeout));
+    assert!(start.elapsed() >= Duration::from_millis(50), "The wait ended before the jitter band");
+    event.delete()
+}
@@ -0,0 +1,38 @@
+use ntsync::{
+    Error,
+    NtSync,
+};
+use rstest::rstest;
+use std::{
+    thread::{
+        Builder,
+        sleep,
+    },
+    time::{
+        Duration,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn signal_to_wakeup(instance: NtSync) -> Result<(), Error> {
+    let probe = instance.new_latency_probe()?;
+    assert_eq!(probe.elapsed(), None);
+    assert_eq!(probe.wait(Some(UNIX_EPOCH))?, None, "An unmarked probe woke up");
+    let waiter_probe = probe.clone();
+    let waiter = match Builder::new().name("latency waiter".to_owned()).spawn(move || waiter_probe.wait(Some(SystemTime::now() + Duration::from_secs(5)))) {
+        Ok(join) => join,
+        Err(error) => panic!("Failed to spawn thread for the test: {error}"),
+    };
+    sleep(Duration::from_millis(50));
+    probe.mark()?;
+    let latency = waiter.join().expect("The waiter panicked")?.expect("The waiter timed out");
+    assert!(latency < Duration::from_secs(1), "Implausible wakeup latency {latency:?}");
+    assert!(probe.elapsed().is_some_and(|elapsed| elapsed >= latency));
+    Ok(())
+}
@@ -0,0 +1,57 @@
+#![cfg(mutex)]
+use log::{
+    LevelFilter,
+    Log,
+    Metadata,
+    Record,
+    kv::Key,
+};
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    OwnerId,
+};
+use rstest::rstest;
+use std::sync::Mutex as StdMutex;
+
+mod fixtures;
+use fixtures::*;
+
+/// Collects the `kind` field of every log line, the test has its own logger instead of the one of test_log to read the fields.
+struct KindLogger {
+    kinds: StdMutex<Vec<String>>,
+}
+
+impl Log for KindLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        if let Some(kind) = record.key_values().get(Key::from_str("kind")) &&
+            let Ok(mut kinds) = self.kinds.lock()
+        {
+            kinds.push(kind.to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: KindLogger = KindLogger {
+    kinds: StdMutex::new(Vec::new()),
+};
+
+#[rstest]
+fn mutex_logs_kind(instance: NtSync) -> Result<(), Error> {
+    log::set_logger(&LOGGER).map_err(|_| Error::InvalidValue)?;
+    log::set_max_level(LevelFilter::Trace);
+    let owner = OwnerId::new(1);
+    let mutex = instance.new_mutex_owned(owner, 1)?;
+    mutex.kill(owner)?;
+    mutex.delete()?;
+    let kinds = LOGGER.kinds.lock().map_err(|_| Error::InvalidValue)?;
+    assert!(kinds.iter().any(|kind| kind == "mutex"), "The kill of the mutex was not logged with its kind: {kinds:?}");
+    Ok(())
+}
@@ -0,0 +1,171 @@
+#![cfg(metrics)]
+use log::*;
+use ntsync::{
+    Error,
+    EventSources,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    ObjectKind,
+    OwnerId,
+};
+use rstest::rstest;
+use std::{
+    os::fd::AsRawFd as _,
+    thread::{
+        Builder,
+        sleep,
+    },
+    time::Duration,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn in_flight_wait(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let thread_data = (instance.clone(), event);
+    let thread = Builder::new()
+        .name("blocked thread".to_owned())
+        .spawn(move || -> Result<(), Error> {
+            let (instance, event) = thread_data;
+            instance.wait_any(hash!(event.into()), None, None, NtSyncFlags::empty(), None)?;
+            Ok(())
+        })
+        .map_err(Error::IOError)?;
+    let mut waits = instance.in_flight_waits();
+    while waits.is_empty() && !thread.is_finished() {
+        sleep(Duration::from_millis(1));
+        waits = instance.in_flight_waits();
+    }
+    trace!("Waits in flight: {waits:?}");
+    assert_eq!(waits.len(), 1, "The blocked wait was not reported");
+    assert_eq!(waits[0].objects, vec![EventSources::Event(event)], "Wrong objects reported");
+    assert_eq!(waits[0].thread.as_deref(), Some("blocked thread"), "Wrong thread reported");
+    assert!(!waits[0].all, "wait_any was reported as wait_all");
+    event.signal()?;
+    match thread.join() {
+        Ok(result) => result?,
+        Err(error) => panic!("Blocked thread panicked: {error:?}"),
+    }
+    assert!(instance.in_flight_waits().is_empty(), "Finished wait is still reported");
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(semaphore)]
+fn profiled_bottleneck(instance: NtSync) -> Result<(), Error> {
+    let early = instance.new_semaphore(1)?;
+    let late = instance.new_semaphore(1)?;
+    let owner = OwnerId::random();
+    instance.wait_all(hash!(early.into(), late.into()), None, Some(owner), NtSyncFlags::empty(), None)?;
+    let thread = Builder::new()
+        .name("release thread".to_owned())
+        .spawn(move || -> Result<(), Error> {
+            sleep(Duration::from_millis(50));
+            early.release(1)?;
+            sleep(Duration::from_millis(200));
+            late.release(1)?;
+            Ok(())
+        })
+        .map_err(Error::IOError)?;
+    let profile = instance.wait_all_profiled(hash!(early.into(), late.into()), None, Some(owner), NtSyncFlags::empty(), None, Duration::from_millis(5))?;
+    match thread.join() {
+        Ok(result) => result?,
+        Err(error) => panic!("Release thread panicked: {error:?}"),
+    }
+    trace!("Profile of the wait: {profile:?}");
+    assert_eq!(profile.bottleneck, Some(EventSources::Semaphore(late)), "The later released semaphore was not the bottleneck");
+    Ok(())
+}
+
+#[test(rstest)]
+fn approx_waiters(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, true)?;
+    assert_eq!(event.approx_waiters(), 0, "Waiters reported before any wait");
+    let mut threads = Vec::new();
+    for id in 0..3 {
+        let (instance, event) = (instance.clone(), event);
+        threads.push(
+            Builder::new()
+                .name(format!("waiter {id}"))
+                .spawn(move || -> Result<(), Error> {
+                    instance.wait_any(hash!(event.into()), None, None, NtSyncFlags::empty(), None)?;
+                    Ok(())
+                })
+                .map_err(Error::IOError)?,
+        );
+    }
+    while event.approx_waiters() < 3 && !threads.iter().any(|thread| thread.is_finished()) {
+        sleep(Duration::from_millis(1));
+    }
+    assert_eq!(event.approx_waiters(), 3, "Not all blocked waits were counted");
+    event.signal()?;
+    for thread in threads {
+        match thread.join() {
+            Ok(result) => result?,
+            Err(error) => panic!("Waiter panicked: {error:?}"),
+        }
+    }
+    assert_eq!(event.approx_waiters(), 0, "Finished waits are still counted");
+    event.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+fn signal_with_waiters(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, true)?;
+    let mut threads = Vec::new();
+    for id in 0..2 {
+        let (instance, event) = (instance.clone(), event);
+        threads.push(
+            Builder::new()
+                .name(format!("waiter {id}"))
+                .spawn(move || -> Result<(), Error> {
+                    instance.wait_any(hash!(event.into()), None, None, NtSyncFlags::empty(), None)?;
+                    Ok(())
+                })
+                .map_err(Error::IOError)?,
+        );
+    }
+    while event.approx_waiters() < 2 && !threads.iter().any(|thread| thread.is_finished()) {
+        sleep(Duration::from_millis(1));
+    }
+    assert_eq!(event.signal_with_waiters()?, (false, 2), "The blocked waiters were not reported");
+    for thread in threads {
+        match thread.join() {
+            Ok(result) => result?,
+            Err(error) => panic!("Waiter panicked: {error:?}"),
+        }
+    }
+    assert_eq!(event.signal_with_waiters()?, (true, 0), "An signal into the void reported waiters");
+    event.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(all(semaphore, mutex))]
+fn device_snapshot(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(true, true)?;
+    let semaphore = instance.new_semaphore(3)?;
+    let mutex = instance.new_mutex()?;
+    let deleted = instance.new_event(false, false)?;
+    deleted.delete()?;
+    let snapshot = instance.snapshot();
+    let kind = |fd| snapshot.objects.iter().find(|entry| entry.fd == fd).map(|entry| entry.kind);
+    assert_eq!(kind(event.as_raw_fd()), Some(ObjectKind::Event), "The event is missing in the snapshot");
+    assert_eq!(kind(semaphore.as_raw_fd()), Some(ObjectKind::Semaphore), "The semaphore is missing in the snapshot");
+    assert_eq!(kind(mutex.as_raw_fd()), Some(ObjectKind::Mutex), "The mutex is missing in the snapshot");
+    assert!(snapshot.objects.iter().all(|entry| entry.status.is_ok()), "Reading an object failed: {snapshot:?}");
+    #[cfg(serialize)]
+    {
+        let json = serde_json::to_string(&snapshot).map_err(|error| Error::IOError(error.into()))?;
+        assert!(json.contains("Semaphore"), "The serialized snapshot misses the semaphore: {json}");
+    }
+    event.delete()?;
+    semaphore.delete()?;
+    mutex.delete()?;
+    Ok(())
+}
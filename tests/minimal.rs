@@ -0,0 +1,21 @@
+#![cfg(minimal)]
+use ntsync::{
+    Error,
+    NtSync,
+    NtSyncFlags,
+};
+use rstest::rstest;
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn minimal_backend_signal_wait(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    assert!(!event.signal()?, "New event was already signaled");
+    let status = instance.wait_any(hash!(event.into()), None, None, NtSyncFlags::empty(), None)?;
+    assert!(!status.alerted, "Wait was alerted without an alert");
+    assert!(!event.status()?.signaled(), "Automatic event was not reset by the wait");
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+};
+use rstest::rstest;
+use std::{
+    collections::HashSet,
+    time::Duration,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn mirror_follows_source(instance: NtSync) -> Result<(), Error> {
+    let source = instance.new_event(false, true)?;
+    let (mirror, handle) = source.mirror(&instance)?;
+    assert!(mirror.status()?.manual_reset(), "The mirror has another reset mode than the source");
+    source.signal()?;
+    instance.wait_any_for(HashSet::from([mirror.into()]), Some(Duration::from_secs(1)), None, NtSyncFlags::empty(), None)?;
+    mirror.reset()?;
+    assert!(source.status()?.signaled(), "Resetting the mirror changed the source");
+    drop(handle);
+    mirror.delete()?;
+    source.delete()?;
+    Ok(())
+}
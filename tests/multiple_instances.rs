@@ -1,6 +1,16 @@
-use std::time::{
-    Duration,
-    SystemTime,
+use std::{
+    env,
+    fs::{
+        File,
+        OpenOptions,
+        remove_file,
+    },
+    os::unix::fs::symlink,
+    process,
+    time::{
+        Duration,
+        SystemTime,
+    },
 };
 
 use log::*;
@@ -33,7 +43,7 @@ fn test_multiple_instances(instance1: NtSync, instance2: NtSync) -> Result<(), E
             error!("Failed to correctly wait on objects of different instances.");
             return Err(Error::InvalidValue);
         },
-        Err(Error::InvalidValue) => {},
+        Err(error) if matches!(error.kind(), Error::InvalidValue) => {},
         Err(other) => {
             error!("Failed to wait on objects from different instances: {other:?}");
             return Err(other);
@@ -41,3 +51,46 @@ fn test_multiple_instances(instance1: NtSync, instance2: NtSync) -> Result<(), E
     }
     Ok(())
 }
+
+#[test(rstest)]
+fn independent_instance(instance: NtSync) -> Result<(), Error> {
+    let independent = instance.new_independent()?;
+    assert_eq!(instance.merge(&independent).map(|_| ()), Err(Error::CrossInstance), "The independent instance shares the device");
+    let event1 = instance.new_event(true, true)?;
+    let event2 = independent.new_event(true, true)?;
+    let result = instance.wait_all(hash!(event1.into(), event2.into()), None, None, NtSyncFlags::default(), None);
+    assert_eq!(result.map(|_| ()), Err(Error::InvalidValue), "The objects of the independent instance were accepted");
+    Ok(())
+}
+
+#[test]
+fn independent_instance_custom_path() -> Result<(), Error> {
+    let path = env::temp_dir().join(format!("ntsync-independent-{}", process::id()));
+    symlink("/dev/ntsync", &path).map_err(Error::IOError)?;
+    let opened = NtSync::with_object_limit(&path, 1).and_then(|instance| Ok((instance.new_independent()?, instance)));
+    remove_file(&path).map_err(Error::IOError)?;
+    let (independent, instance) = opened?;
+    assert_eq!(independent.object_limit(), Some((0, 1)), "The independent instance lost the object limit");
+    let _event = independent.new_event(false, false)?;
+    assert_eq!(independent.new_event(false, false).err(), Some(Error::ResourceExhausted));
+    assert_eq!(instance.object_limit(), Some((0, 1)), "The independent instance shares the limit");
+    assert_eq!(instance.new_independent().err(), Some(Error::NotExist), "The device was not opened from the removed path");
+    Ok(())
+}
+
+#[test]
+fn independent_instance_from_file() -> Result<(), Error> {
+    let file: File = OpenOptions::new().read(true).write(true).open("/dev/ntsync").map_err(Error::IOError)?;
+    let instance = NtSync::from_file(file)?;
+    assert_eq!(instance.new_independent().err(), Some(Error::NoDevicePath));
+    Ok(())
+}
+
+#[test(rstest)]
+fn merge_instances(instance1: NtSync, instance2: NtSync) -> Result<(), Error> {
+    assert_eq!(instance1.merge(&instance2).map(|_| ()), Err(Error::CrossInstance), "Independent instances were merged");
+    let merged = instance1.merge(&instance1.clone())?;
+    let event = instance1.new_event(true, true)?;
+    merged.wait_all(hash!(event.into()), None, None, NtSyncFlags::default(), None)?;
+    Ok(())
+}
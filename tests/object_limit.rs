@@ -0,0 +1,40 @@
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+};
+use test_log::test;
+
+#[test]
+fn object_limit() -> Result<(), Error> {
+    let instance = NtSync::with_object_limit("/dev/ntsync", 3)?;
+    let first = instance.new_event(false, false)?;
+    let _second = instance.new_event(false, true)?;
+    let _third = instance.new_event(true, false)?;
+    assert_eq!(instance.object_limit(), Some((3, 3)));
+    assert_eq!(instance.new_event(false, false).err(), Some(Error::ResourceExhausted));
+    assert_eq!(instance.clone().new_event(false, false).err(), Some(Error::ResourceExhausted), "An clone does not share the limit");
+
+    first.delete()?;
+    assert_eq!(instance.object_limit(), Some((2, 3)));
+    let _replacement = instance.new_event(false, false)?;
+    assert_eq!(instance.new_event(false, false).err(), Some(Error::ResourceExhausted));
+    Ok(())
+}
+
+#[test]
+fn object_limit_dropped_wrapper() -> Result<(), Error> {
+    let instance = NtSync::with_object_limit("/dev/ntsync", 1)?;
+    let parker = instance.new_parker()?;
+    assert_eq!(instance.new_event(false, false).err(), Some(Error::ResourceExhausted));
+    drop(parker);
+    assert_eq!(instance.object_limit(), Some((0, 1)));
+    instance.new_event(false, false)?.delete()
+}
+
+#[test]
+fn unlimited_instance() -> Result<(), Error> {
+    let instance = NtSync::new()?;
+    assert_eq!(instance.object_limit(), None);
+    instance.new_event(false, false)?.delete()
+}
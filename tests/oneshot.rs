@@ -0,0 +1,38 @@
+use ntsync::{
+    Error,
+    NtSync,
+};
+use rstest::rstest;
+use std::thread::{
+    Builder,
+    scope,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn one_shot_releases_all_waiters(instance: NtSync) -> Result<(), Error> {
+    let latch = instance.new_one_shot()?;
+    assert!(!latch.is_fired()?, "New latch is already fired");
+    scope(|scope| -> Result<(), Error> {
+        let mut threads = Vec::new();
+        for id in 0..3 {
+            let latch = &latch;
+            threads.push(Builder::new().name(format!("waiter {id}")).spawn_scoped(scope, move || latch.wait(None)).map_err(Error::IOError)?);
+        }
+        assert!(!latch.fire()?, "Latch was fired before the first fire");
+        assert!(latch.fire()?, "Second fire did not report the latch as fired");
+        for thread in threads {
+            match thread.join() {
+                Ok(result) => assert!(result?, "Waiter did not pass"),
+                Err(error) => panic!("Waiter panicked: {error:?}"),
+            }
+        }
+        Ok(())
+    })?;
+    assert!(latch.is_fired()?, "Latch was reset");
+    assert!(latch.wait(None)?, "Later waiter did not pass");
+    Ok(())
+}
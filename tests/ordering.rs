@@ -0,0 +1,37 @@
+use ntsync::{
+    Error,
+    EventSources,
+    NtSync,
+};
+use rstest::rstest;
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+#[cfg(all(mutex, semaphore))]
+fn sort_mixed_sources(instance: NtSync) -> Result<(), Error> {
+    let event1 = instance.new_event(false, false)?;
+    let semaphore = instance.new_semaphore(1)?;
+    let mutex = instance.new_mutex()?;
+    let event2 = instance.new_event(false, true)?;
+
+    let mut sources: Vec<EventSources> = vec![
+        event2.into(),
+        event1.into(),
+        semaphore.into(),
+        mutex.into(),
+    ];
+    let mut reversed = sources.clone();
+    reversed.reverse();
+    sources.sort();
+    reversed.sort();
+    assert_eq!(sources, reversed, "Sorting is not deterministic");
+
+    assert!(matches!(sources[0], EventSources::Mutex(_)), "Mutex is not sorted first: {sources:?}");
+    assert!(matches!(sources[1], EventSources::Semaphore(_)), "Semaphore is not sorted second: {sources:?}");
+    assert_eq!(sources[2], EventSources::Event(event1.min(event2)), "Events are not sorted by the fd");
+    assert_eq!(sources[3], EventSources::Event(event1.max(event2)), "Events are not sorted by the fd");
+    Ok(())
+}
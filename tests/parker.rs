@@ -0,0 +1,50 @@
+use ntsync::{
+    Error,
+    NtSync,
+};
+use rstest::rstest;
+use std::{
+    thread::{
+        Builder,
+        sleep,
+    },
+    time::{
+        Duration,
+        Instant,
+        UNIX_EPOCH,
+    },
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn unpark_before_park(instance: NtSync) -> Result<(), Error> {
+    let parker = instance.new_parker()?;
+    parker.unparker().unpark()?;
+    assert!(parker.park(None)?, "The earlier unpark was lost");
+    assert!(!parker.park(Some(UNIX_EPOCH))?, "The unpark was consumed twice");
+    Ok(())
+}
+
+#[test(rstest)]
+fn park_blocks_until_unpark(instance: NtSync) -> Result<(), Error> {
+    let parker = instance.new_parker()?;
+    let unparker = parker.unparker();
+    let delay = Duration::from_millis(50);
+    let start = Instant::now();
+    let thread = Builder::new()
+        .name("unparker".to_owned())
+        .spawn(move || {
+            sleep(delay);
+            unparker.unpark()
+        })
+        .map_err(Error::IOError)?;
+    assert!(parker.park(None)?, "The park did not return after the unpark");
+    assert!(start.elapsed() >= delay, "The park returned before the unpark");
+    match thread.join() {
+        Ok(result) => result,
+        Err(error) => panic!("The unparker panicked: {error:?}"),
+    }
+}
@@ -0,0 +1,34 @@
+use nix::libc;
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+};
+use rstest::rstest;
+use std::os::fd::AsRawFd as _;
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+/// Lowers the limit of open files to the current number, so the next creation fails with `EMFILE`.
+#[test(rstest)]
+fn creation_over_limit(instance: NtSync) -> Result<(), Error> {
+    let probe = instance.new_event(false, false)?;
+    let highest = probe.as_raw_fd() as libc::rlim_t;
+    probe.delete()?;
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    assert_eq!(unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &raw mut limit) }, 0, "Failed to read the limit of open files");
+    let lowered = libc::rlimit {
+        rlim_cur: highest,
+        rlim_max: limit.rlim_max,
+    };
+    assert_eq!(unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &raw const lowered) }, 0, "Failed to lower the limit of open files");
+    let result = instance.new_event(false, false);
+    assert_eq!(unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &raw const limit) }, 0, "Failed to restore the limit of open files");
+    assert_eq!(result.map(|_| ()), Err(Error::QuotaExceeded), "The limit of open files was not reported");
+    Ok(())
+}
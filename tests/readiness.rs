@@ -0,0 +1,51 @@
+use ntsync::{
+    Error,
+    NtSync,
+    NtSyncFlags,
+    OwnerId,
+};
+use rstest::rstest;
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+#[cfg(all(mutex, semaphore))]
+fn readiness_of_mixed_set(instance: NtSync) -> Result<(), Error> {
+    let owner = OwnerId::new(1);
+    let other = OwnerId::new(2);
+    let signaled = instance.new_event(true, true)?;
+    let unsignaled = instance.new_event(false, true)?;
+    let semaphore = instance.new_semaphore(1)?;
+    let empty_semaphore = instance.new_semaphore(1)?;
+    let free_mutex = instance.new_mutex()?;
+    let owned_mutex = instance.new_mutex()?;
+    instance.wait_all(hash!(empty_semaphore.into(), owned_mutex.into()), None, Some(owner), NtSyncFlags::empty(), None)?;
+
+    let sources = [
+        signaled.into(),
+        unsignaled.into(),
+        semaphore.into(),
+        empty_semaphore.into(),
+        free_mutex.into(),
+        owned_mutex.into(),
+    ];
+    let flags: Vec<bool> = instance.readiness(&sources, Some(other)).into_iter().map(|(_, ready)| ready).collect();
+    assert_eq!(
+        flags,
+        vec![
+            true, false, true, false, true, false
+        ],
+        "Wrong readiness for another owner"
+    );
+    let flags: Vec<bool> = instance.readiness(&sources, Some(owner)).into_iter().map(|(_, ready)| ready).collect();
+    assert_eq!(
+        flags,
+        vec![
+            true, false, true, false, true, true
+        ],
+        "Wrong readiness for the owner of the mutex"
+    );
+    Ok(())
+}
@@ -0,0 +1,59 @@
+#![cfg(record)]
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    Outcome,
+    Trace,
+};
+use rstest::rstest;
+use std::{
+    collections::HashSet,
+    time::Duration,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+fn scenario(instance: &NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    event.signal()?;
+    instance.wait_any_for(HashSet::from([event.into()]), Some(Duration::from_secs(1)), None, NtSyncFlags::empty(), None)?;
+    assert_eq!(
+        instance.wait_any_for(HashSet::from([event.into()]), Some(Duration::from_millis(10)), None, NtSyncFlags::empty(), None).err(),
+        Some(Error::Timeout)
+    );
+    event.status()?;
+    event.delete()
+}
+
+#[test(rstest)]
+fn record_and_replay(instance: NtSync) -> Result<(), Error> {
+    instance.start_recording();
+    scenario(&instance)?;
+    let trace = instance.stop_recording().unwrap_or_default();
+    let outcomes: Vec<&Outcome> = trace.entries.iter().map(|entry| &entry.outcome).collect();
+    assert_eq!(
+        outcomes,
+        [
+            &Outcome::Created(0),
+            &Outcome::Value(0),
+            &Outcome::Signaled(0),
+            &Outcome::Failed(nix::libc::ETIMEDOUT),
+            &Outcome::Status(0, 0),
+            &Outcome::Done
+        ],
+        "The recorded outcomes are wrong"
+    );
+
+    let stored = serde_json::to_string(&trace).map_err(|error| Error::IOError(error.into()))?;
+    let loaded: Trace = serde_json::from_str(&stored).map_err(|error| Error::IOError(error.into()))?;
+    assert_eq!(loaded, trace, "The trace changed while it was stored");
+
+    let replayed = instance.replay(&loaded)?;
+    assert_eq!(replayed, trace, "The replay had other outcomes");
+    assert_eq!(instance.stop_recording(), None, "The replay left an recording active");
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use nix::libc;
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    RetryInfo,
+};
+use rstest::rstest;
+use std::{
+    mem,
+    ptr,
+    sync::mpsc,
+    thread::{
+        Builder,
+        sleep,
+    },
+    time::{
+        Duration,
+        SystemTime,
+    },
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+const SIGNALS: u32 = 3;
+
+extern "C" fn ignore(_signal: libc::c_int) {}
+
+/// Installs an handler for `SIGUSR1` without `SA_RESTART`, so the signal interrupts the wait with `EINTR` instead of killing the process.
+fn install_handler() {
+    let mut action: libc::sigaction = unsafe { mem::zeroed() };
+    action.sa_sigaction = ignore as *const () as libc::sighandler_t;
+    assert_eq!(unsafe { libc::sigemptyset(&raw mut action.sa_mask) }, 0);
+    assert_eq!(unsafe { libc::sigaction(libc::SIGUSR1, &raw const action, ptr::null_mut()) }, 0, "Failed to install the signal handler");
+}
+
+#[test(rstest)]
+fn hook_per_interrupt(instance: NtSync) -> Result<(), Error> {
+    install_handler();
+    let event = instance.new_event(false, false)?;
+    let (sender, receiver) = mpsc::channel();
+    let waiter_instance = instance.clone();
+    let waiter = match Builder::new().name("interrupted waiter".to_owned()).spawn(move || {
+        if sender.send(unsafe { libc::pthread_self() }).is_err() {
+            return Err(Error::InvalidValue);
+        }
+        let mut retries: Vec<RetryInfo> = Vec::new();
+        let timeout = Some(SystemTime::now() + Duration::from_secs(10));
+        waiter_instance.wait_any_with_hook(hash!(event.into()), timeout, None, NtSyncFlags::WaitRealtime, None, |info| retries.push(info))?;
+        Ok(retries)
+    }) {
+        Ok(join) => join,
+        Err(error) => panic!("Failed to spawn thread for the test: {error}"),
+    };
+    let thread = receiver.recv().expect("The waiter did not send its thread");
+    for _ in 0..SIGNALS {
+        sleep(Duration::from_millis(100));
+        assert_eq!(unsafe { libc::pthread_kill(thread, libc::SIGUSR1) }, 0, "Failed to signal the waiter");
+    }
+    sleep(Duration::from_millis(100));
+    event.signal()?;
+    let retries = waiter.join().expect("The waiter panicked")?;
+    assert_eq!(retries.len(), SIGNALS as usize, "The hook was not called once per signal: {retries:?}");
+    for (number, retry) in (1..).zip(&retries) {
+        assert_eq!(retry.attempt, number);
+    }
+    assert!(retries.windows(2).all(|pair| pair[0].elapsed <= pair[1].elapsed), "The elapsed time went backwards: {retries:?}");
+    event.delete()
+}
@@ -0,0 +1,41 @@
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+};
+use rstest::rstest;
+use std::time::{
+    Duration,
+    SystemTime,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn alert_is_reset_after_the_wait(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let alert = instance.new_reusable_alert()?;
+    alert.fire()?;
+    for _ in 0..2 {
+        let (status, reset) = instance.wait_any_reusable(hash!(event.into()), None, None, NtSyncFlags::WaitRealtime, &alert)?;
+        assert!(status.alerted, "The fired alert did not stop the wait");
+        assert!(reset.alerted());
+        drop(reset);
+        assert!(!alert.is_fired()?, "The alert was not reset after the wait");
+        let result =
+            instance.wait_any_reusable(hash!(event.into()), Some(SystemTime::now() + Duration::from_millis(50)), None, NtSyncFlags::WaitRealtime, &alert);
+        assert_eq!(result.err(), Some(Error::Timeout), "The second wait did not block");
+        alert.fire()?;
+    }
+    event.signal()?;
+    let (status, reset) = instance.wait_all_reusable(hash!(event.into()), None, None, NtSyncFlags::WaitRealtime, &alert)?;
+    // The alert is checked after the objects, so the signaled event wins and the fire stays for the next wait.
+    assert!(!status.alerted);
+    assert!(!reset.alerted());
+    drop(reset);
+    assert!(alert.is_fired()?, "The fire was lost without stopping an wait");
+    event.delete()
+}
@@ -0,0 +1,21 @@
+use ntsync::{
+    Error,
+    NtSync,
+};
+use rstest::rstest;
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn self_test(instance: NtSync) -> Result<(), Error> {
+    let report = instance.self_test();
+    assert!(report.is_ok(), "Failed primitives: {:?}", report.failures());
+    report.event?;
+    #[cfg(semaphore)]
+    report.semaphore?;
+    #[cfg(mutex)]
+    report.mutex?;
+    Ok(())
+}
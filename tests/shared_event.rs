@@ -0,0 +1,43 @@
+use ntsync::{
+    Error,
+    NtSync,
+};
+use rstest::rstest;
+use std::{
+    os::fd::{
+        AsRawFd as _,
+        RawFd,
+    },
+    thread::Builder,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+fn is_open(fd: RawFd) -> bool {
+    unsafe { nix::libc::fcntl(fd, nix::libc::F_GETFD) != -1 }
+}
+
+#[test(rstest)]
+fn closed_after_last_clone(instance: NtSync) -> Result<(), Error> {
+    let shared = instance.new_shared_event(false, true)?;
+    let fd = shared.event().as_raw_fd();
+    let clone = shared.clone();
+    let thread = Builder::new()
+        .name("shared event".to_owned())
+        .spawn(move || -> Result<bool, Error> {
+            let signaled = clone.wait(None)?;
+            assert!(is_open(clone.event().as_raw_fd()), "The file descriptor was closed while an clone was alive");
+            Ok(signaled)
+        })
+        .map_err(Error::IOError)?;
+    shared.signal()?;
+    drop(shared);
+    match thread.join() {
+        Ok(result) => assert!(result?, "The wait did not see the signal"),
+        Err(error) => panic!("The waiting thread panicked: {error:?}"),
+    }
+    assert!(!is_open(fd), "The file descriptor is still open after all clones were dropped");
+    Ok(())
+}
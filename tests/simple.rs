@@ -1,12 +1,23 @@
 use log::*;
+#[cfg(mutex)]
+use ntsync::Mutex;
 use ntsync::{
     Error,
+    EventSources,
     NTSyncObjects as _,
+    NonZeroOwnerId,
     NtSync,
     NtSyncFlags,
     OwnerId,
 };
 use rstest::rstest;
+use std::{
+    collections::HashSet,
+    fs::File,
+    os::fd::AsRawFd as _,
+    path::PathBuf,
+    time::Duration,
+};
 use test_log::test;
 
 mod fixtures;
@@ -34,7 +45,13 @@ fn ntsync_event(instance: NtSync) -> Result<(), Error> {
 fn ntsync_mutex(instance: NtSync) -> Result<(), Error> {
     let owner = OwnerId::random();
     let mutex = instance.new_mutex()?;
-    assert_eq!(mutex.unlock(owner), Err(Error::PermissionDenied));
+    assert_eq!(
+        mutex.unlock(owner),
+        Err(Error::WrongOwner {
+            expected: owner,
+            actual: None,
+        })
+    );
     instance.wait_all(hash!(mutex.into()), None, Some(owner), NtSyncFlags::empty(), None)?;
     Ok(())
 }
@@ -54,3 +71,389 @@ fn ntsync_semaphore(instance: NtSync) -> Result<(), Error> {
     semaphore.release(1)?;
     Ok(())
 }
+
+#[test(rstest)]
+fn ntsync_ping(instance: NtSync) -> Result<(), Error> {
+    instance.ping()
+}
+
+#[test(rstest)]
+fn ntsync_create_and_wait_event(instance: NtSync) -> Result<(), Error> {
+    let (event, signaled) = instance.create_and_wait_event(true, false, None)?;
+    assert!(signaled, "Wait on the signaled event did not return signaled");
+    assert!(!event.status()?.signaled(), "Automatic event was not reset by the wait");
+    event.delete()
+}
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn ntsync_mutex_recursion(instance: NtSync) -> Result<(), Error> {
+    let owner = OwnerId::random();
+    let mutex = instance.new_mutex()?;
+    let status = mutex.read()?;
+    assert_eq!(status.owner(), None, "New mutex has an owner");
+    assert_eq!(status.recursion_count(), 0, "New mutex is locked");
+    instance.wait_all(hash!(mutex.into()), None, Some(owner), NtSyncFlags::empty(), None)?;
+    instance.wait_all(hash!(mutex.into()), None, Some(owner), NtSyncFlags::empty(), None)?;
+    assert_eq!(mutex.read()?.recursion_count(), 2, "Recursive locking did not increment the count");
+    mutex.unlock(owner)?;
+    assert_eq!(mutex.read()?.recursion_count(), 1, "Unlocking did not decrement the count");
+    mutex.unlock(owner)?;
+    let status = mutex.read()?;
+    assert_eq!(status.owner(), None, "Owner was not released");
+    assert_eq!(status.recursion_count(), 0, "Mutex is still locked");
+    Ok(())
+}
+
+#[test(rstest)]
+fn ntsync_strict_event_builder(instance: NtSync) -> Result<(), Error> {
+    let result = instance.event_builder().signaled(true).strict().build();
+    assert_eq!(result.map(|_| ()), Err(Error::InvalidValue), "Strict mode accepted an signaled automatic event");
+    let event = instance.event_builder().signaled(true).build()?;
+    assert!(event.status()?.signaled(), "Permissive mode did not create an signaled event");
+    let event = instance.event_builder().signaled(true).manual(true).strict().build()?;
+    assert!(event.status()?.manual_reset(), "Strict mode did not create an manual event");
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn non_zero_owner(instance: NtSync) -> Result<(), Error> {
+    assert_eq!(NonZeroOwnerId::new(0), None, "The owner zero was accepted");
+    assert_eq!(NonZeroOwnerId::try_from(OwnerId::default()), Err(Error::InvalidValue), "The default owner was accepted");
+    let Some(owner) = NonZeroOwnerId::new(7) else {
+        panic!("The owner 7 was rejected");
+    };
+    let mutex = instance.new_mutex()?;
+    instance.wait_all(hash!(mutex.into()), None, Some(owner.into()), NtSyncFlags::empty(), None)?;
+    mutex.unlock(owner.into())?;
+    mutex.delete()?;
+    Ok(())
+}
+
+#[test]
+fn from_file() -> Result<(), Error> {
+    let file = File::open("/dev/ntsync").map_err(Error::IOError)?;
+    let instance = NtSync::from_file(file)?;
+    let event = instance.new_event(true, false)?;
+    assert!(event.status()?.signaled(), "The event of the wrapped device is not signaled");
+    event.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+fn event_snapshot_flags(instance: NtSync) -> Result<(), Error> {
+    for (signaled, manual) in [
+        (false, false),
+        (true, false),
+        (false, true),
+        (true, true),
+    ] {
+        let event = instance.new_event(signaled, manual)?;
+        let snapshot = event.status()?;
+        assert_eq!(snapshot.signaled(), signaled, "Wrong signaled flag for signaled={signaled} manual={manual}");
+        assert_eq!(snapshot.manual_reset(), manual, "Wrong manual flag for signaled={signaled} manual={manual}");
+        event.delete()?;
+    }
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn mutex_depth_limit(instance: NtSync) -> Result<(), Error> {
+    let owner = OwnerId::new(5);
+    let mutex = instance.new_mutex_owned(owner, Mutex::max_depth() - 1)?;
+    instance.lock_recursive(mutex, owner, None)?;
+    assert_eq!(mutex.read()?.recursion_count(), Mutex::max_depth(), "The mutex is not at the maximum depth");
+    assert_eq!(instance.lock_recursive(mutex, owner, None), Err(Error::DepthExceeded), "The lock exceeded the maximum depth");
+    mutex.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn mutex_depth_after_wait(instance: NtSync) -> Result<(), Error> {
+    let owner = OwnerId::new(3);
+    let mutex = instance.new_mutex()?;
+    let status = instance.wait_all(hash!(mutex.into()), None, Some(owner), NtSyncFlags::empty(), None)?;
+    assert_eq!(status.mutex_depth(&mutex)?, 1, "The first lock reported the wrong depth");
+    let status = instance.wait_all(hash!(mutex.into()), None, Some(owner), NtSyncFlags::empty(), None)?;
+    assert_eq!(status.mutex_depth(&mutex)?, 2, "The second lock did not increase the depth");
+    mutex.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+fn device_opened_read_write(instance: NtSync) -> Result<(), Error> {
+    let flags = unsafe { nix::libc::fcntl(instance.as_raw_fd(), nix::libc::F_GETFL) };
+    assert_ne!(flags, -1, "fcntl failed");
+    assert_eq!(flags & nix::libc::O_ACCMODE, nix::libc::O_RDWR, "The device is not opened read-write");
+    Ok(())
+}
+
+#[test(rstest)]
+fn typed_events(instance: NtSync) -> Result<(), Error> {
+    let manual = instance.new_manual_event(true)?;
+    let auto = instance.new_auto_event(true)?;
+    assert!(manual.status()?.manual_reset(), "The manual event was created as automatic event");
+    assert!(!auto.status()?.manual_reset(), "The automatic event was created as manual event");
+    assert!(manual.reset()?, "The manual event was not signaled");
+    assert!(!manual.status()?.signaled(), "The manual event was not reset");
+    instance.wait_any(hash!(auto.into()), None, None, NtSyncFlags::empty(), None)?;
+    assert!(!auto.status()?.signaled(), "The wait did not consume the signal of the automatic event");
+    manual.delete()?;
+    auto.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn mutex_non_recursive(instance: NtSync) -> Result<(), Error> {
+    let owner = OwnerId::new(4);
+    let mutex = instance.new_mutex()?;
+    mutex.lock_non_recursive(&instance, owner, None)?;
+    assert_eq!(mutex.lock_non_recursive(&instance, owner, None), Err(Error::WouldRecurse), "The owner locked the mutex again");
+    assert_eq!(mutex.read()?.recursion_count(), 1, "The rejected lock changed the depth");
+    mutex.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn mutex_acquirable_by(instance: NtSync) -> Result<(), Error> {
+    let (owner_a, owner_b) = (OwnerId::new(1), OwnerId::new(2));
+    let mutex = instance.new_mutex()?;
+    assert!(mutex.acquirable_by(owner_a)? && mutex.acquirable_by(owner_b)?, "The free mutex is not acquirable");
+    mutex.lock_non_recursive(&instance, owner_a, None)?;
+    assert!(mutex.acquirable_by(owner_a)?, "The holder can not lock the mutex again");
+    assert!(!mutex.acquirable_by(owner_b)?, "The mutex held by another owner is acquirable");
+    mutex.unlock(owner_a)?;
+    assert!(mutex.acquirable_by(owner_b)?, "The unlocked mutex is not acquirable");
+    mutex.delete()
+}
+
+#[test(rstest)]
+fn pending_signal(instance: NtSync) -> Result<(), Error> {
+    let auto = instance.new_event(true, false)?;
+    let manual = instance.new_event(true, true)?;
+    let status = auto.status()?;
+    assert!(status.has_pending_signal() && !status.manual_reset(), "The signaled auto event releases no waiter");
+    let status = manual.status()?;
+    assert!(status.has_pending_signal() && status.manual_reset(), "The signaled manual event releases no waiters");
+
+    instance.wait_any(HashSet::from([auto.into()]), None, None, NtSyncFlags::empty(), None)?;
+    assert!(!auto.status()?.has_pending_signal(), "The wait did not consume the signal of the auto event");
+    instance.wait_any(HashSet::from([manual.into()]), None, None, NtSyncFlags::empty(), None)?;
+    assert!(manual.status()?.has_pending_signal(), "The wait consumed the signal of the manual event");
+    auto.delete()?;
+    manual.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+fn reclaim_unconsumed_signal(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    event.signal()?;
+    assert!(event.reclaim_if_unconsumed(Duration::from_millis(10))?, "The unconsumed signal was not reclaimed");
+    assert!(!event.status()?.signaled(), "The event is still signaled");
+    assert!(!event.reclaim_if_unconsumed(Duration::ZERO)?, "An unsignaled event was reclaimed");
+    event.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+fn event_with_mode(instance: NtSync) -> Result<(), Error> {
+    let auto = instance.new_event(true, false)?;
+    let manual = auto.with_mode(&instance, true)?;
+    let status = manual.status()?;
+    assert!(status.manual_reset(), "The new event is not manual");
+    assert!(status.signaled(), "The new event lost the signal");
+    manual.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(semaphore)]
+fn semaphore_in_use(instance: NtSync) -> Result<(), Error> {
+    let semaphore = instance.new_semaphore(5)?;
+    for _ in 0..3 {
+        instance.wait_any(hash!(semaphore.into()), None, None, NtSyncFlags::empty(), None)?;
+    }
+    let status = semaphore.read()?;
+    assert_eq!(status.in_use(), 3, "The acquired permits are not counted as in use");
+    assert_eq!(status.available(), 2, "The remaining permits are not available");
+    semaphore.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(semaphore)]
+fn acquire_and_remaining(instance: NtSync) -> Result<(), Error> {
+    let semaphore = instance.new_semaphore(3)?;
+    for expected in [
+        2, 1, 0,
+    ] {
+        assert_eq!(semaphore.acquire_and_remaining(&instance, None)?, expected, "The remaining count is wrong");
+    }
+    semaphore.release(3)?;
+    semaphore.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn recover_abandoned_mutex(instance: NtSync) -> Result<(), Error> {
+    let (dead, first, second, third) = (OwnerId::new(1), OwnerId::new(2), OwnerId::new(3), OwnerId::new(4));
+    let mutex = instance.new_mutex_owned(dead, 1)?;
+    mutex.kill(dead)?;
+
+    let Err(recovery) = instance.lock_mutex_recoverable(mutex, first, None)? else {
+        panic!("The abandoned mutex was locked without recovery");
+    };
+    recovery.abandon()?;
+    let Err(recovery) = instance.lock_mutex_recoverable(mutex, second, None)? else {
+        panic!("The abandoned mutex was not abandoned again");
+    };
+    let guard = recovery.mark_consistent();
+    assert_eq!(mutex.read()?.owner(), Some(second), "The consistent guard does not hold the mutex");
+    drop(guard);
+    let Ok(guard) = instance.lock_mutex_recoverable(mutex, third, None)? else {
+        panic!("The mutex is still abandoned after it was marked consistent");
+    };
+    drop(guard);
+    mutex.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn unlock_wrong_owner(instance: NtSync) -> Result<(), Error> {
+    let (owner_a, owner_b) = (OwnerId::new(1), OwnerId::new(2));
+    let mutex = instance.new_mutex()?;
+    mutex.lock_non_recursive(&instance, owner_a, None)?;
+    assert_eq!(
+        mutex.unlock(owner_b),
+        Err(Error::WrongOwner {
+            expected: owner_b,
+            actual: Some(owner_a),
+        }),
+        "The error does not report both owners"
+    );
+    mutex.unlock(owner_a)?;
+    mutex.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn lock_if_free(instance: NtSync) -> Result<(), Error> {
+    let (owner_a, owner_b) = (OwnerId::new(1), OwnerId::new(2));
+    let mutex = instance.new_mutex()?;
+    assert!(mutex.lock_if_free(&instance, owner_a)?, "The free mutex was not locked");
+    assert!(!mutex.lock_if_free(&instance, owner_b)?, "The mutex held by another owner was locked");
+    assert!(!mutex.lock_if_free(&instance, owner_a)?, "The mutex was locked recursively");
+    assert_eq!(mutex.read()?.recursion_count(), 1, "The failed attempts changed the mutex");
+    mutex.unlock(owner_a)?;
+    mutex.delete()?;
+    Ok(())
+}
+
+#[test]
+fn flag_names() {
+    assert!(NtSyncFlags::empty().active_flags().is_empty(), "An empty set of flags has names");
+    assert_eq!(NtSyncFlags::WaitRealtime.active_flags(), ["WaitRealtime"], "The name of WaitRealtime is wrong");
+    assert_eq!(NtSyncFlags::WaitRealtime.to_string(), "WaitRealtime", "The flags are displayed wrong");
+}
+
+#[test(rstest)]
+fn proc_fd_path(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let source = EventSources::from(event);
+    let fd = event.as_raw_fd();
+    assert_eq!(source.proc_fd_path(None), PathBuf::from(format!("/proc/self/fd/{fd}")), "The path of the own process is wrong");
+    assert_eq!(source.proc_fd_path(Some(42)), PathBuf::from(format!("/proc/42/fd/{fd}")), "The path of an other process is wrong");
+    event.delete()
+}
+
+#[test(rstest)]
+fn poll_event_statuses(instance: NtSync) -> Result<(), Error> {
+    let events = [
+        instance.new_event(true, true)?,
+        instance.new_event(false, false)?,
+        instance.new_event(true, false)?,
+    ];
+    let statuses = instance.poll_event_statuses(&events);
+    assert_eq!(statuses.len(), events.len(), "Not every event was read");
+    for (event, status) in events.iter().zip(statuses) {
+        assert_eq!(status?, event.status()?, "The batched read differs from the single read");
+    }
+    for event in events {
+        event.delete()?;
+    }
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(all(mutex, semaphore))]
+fn draining_set(instance: NtSync) -> Result<(), Error> {
+    let mutex = instance.new_mutex()?;
+    let semaphore = instance.new_semaphore(2)?;
+    let event = instance.new_event(true, true)?;
+    let owner = OwnerId::random();
+    let set = hash!(mutex.into(), semaphore.into(), event.into());
+    instance.wait_all(set.clone(), None, Some(owner), NtSyncFlags::empty(), None)?;
+    {
+        let draining = instance.draining(&set, owner);
+        assert_eq!(draining.owner(), owner);
+        assert_eq!(mutex.read()?.owner(), Some(owner));
+        assert_eq!(semaphore.read()?.count, 1);
+    }
+    assert_eq!(mutex.read()?.owner(), None, "The mutex was not unlocked");
+    assert_eq!(semaphore.read()?.count, 2, "The semaphore was not released");
+    assert!(!event.status()?.signaled(), "The event was not reset");
+    for object in set {
+        object.delete()?;
+    }
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(semaphore)]
+fn semaphore_drain_all(instance: NtSync) -> Result<(), Error> {
+    let semaphore = instance.new_semaphore(5)?;
+    instance.wait_all(hash!(semaphore.into()), None, None, NtSyncFlags::empty(), None)?;
+    let before = semaphore.read()?.count;
+    assert_eq!(before, 4);
+    assert_eq!(semaphore.drain_all(&instance)?, before);
+    assert_eq!(semaphore.read()?.count, 0);
+    assert_eq!(semaphore.drain_all(&instance)?, 0, "An empty semaphore was drained");
+    semaphore.delete()
+}
+
+#[test(rstest)]
+#[cfg(semaphore)]
+fn wait_all_exhausted_semaphore(instance: NtSync) -> Result<(), Error> {
+    let empty = instance.new_semaphore(1)?;
+    instance.wait_all(hash!(empty.into()), None, None, NtSyncFlags::empty(), None)?;
+    let available = instance.new_semaphore(1)?;
+    let event = instance.new_event(true, true)?;
+    // Without an timeout the wait would block forever, so only the pre-check can return here.
+    let result = instance.wait_all_nonblocking_semaphores(hash!(empty.into(), available.into(), event.into()), None, None, NtSyncFlags::empty(), None);
+    assert_eq!(result.err(), Some(Error::WouldBlock));
+    assert_eq!(available.read()?.count, 1, "The failed wait took an permit");
+    instance.wait_all_nonblocking_semaphores(hash!(available.into(), event.into()), None, None, NtSyncFlags::empty(), None)?;
+    assert_eq!(available.read()?.count, 0, "The wait did not take the permit");
+    empty.delete()?;
+    available.delete()?;
+    event.delete()
+}
+
+#[test(rstest)]
+fn event_read_through_trait(instance: NtSync) -> Result<(), Error> {
+    fn read<T: ntsync::NTSyncObjects>(object: &T) -> Result<T::Status, Error> {
+        object.read()
+    }
+    let event = instance.new_event(true, true)?;
+    assert_eq!(read(&event)?, event.status()?, "The trait read differs from the status");
+    event.delete()
+}
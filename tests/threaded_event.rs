@@ -1,13 +1,28 @@
 use log::*;
 use ntsync::{
     Error,
+    NTSyncObjects as _,
     NtSync,
     NtSyncFlags,
 };
 use rstest::rstest;
-use std::thread::{
-    Builder,
-    JoinHandle,
+use std::{
+    sync::{
+        Arc,
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+    },
+    thread::{
+        Builder,
+        JoinHandle,
+        sleep,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
 };
 use test_log::test;
 
@@ -31,3 +46,49 @@ fn test_event_locking(instance: NtSync) -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test(rstest)]
+fn test_event_wait_until(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let signals = Arc::new(AtomicUsize::new(0));
+    let thread_data = (Arc::clone(&signals), event);
+    let thread: JoinHandle<Result<(), Error>> = match Builder::new().name("signal thread".to_owned()).spawn::<_, Result<(), Error>>(move || {
+        let (signals, event) = thread_data;
+        for _ in 0..2 {
+            sleep(Duration::from_millis(50));
+            signals.fetch_add(1, Ordering::SeqCst);
+            event.signal()?;
+        }
+        Ok(())
+    }) {
+        Ok(join) => join,
+        Err(error) => panic!("Failed to spawn thread for the test: {error}"),
+    };
+
+    let mut checks = 0;
+    let start = Instant::now();
+    let reached = event.wait_until(&instance, Some(Duration::from_secs(2)), || {
+        checks += 1;
+        Ok(signals.load(Ordering::SeqCst) >= 2)
+    })?;
+    assert!(reached);
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert!(checks >= 3, "the predicate was only checked {checks} times");
+    thread.join().expect("the signal thread panicked")?;
+
+    assert!(!event.wait_until(&instance, Some(Duration::from_millis(50)), || Ok(false))?);
+    Ok(())
+}
+
+#[test(rstest)]
+fn event_wait_until_max_timeout(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(true, false)?;
+    let mut checks = 0;
+    let reached = event.wait_until(&instance, Some(Duration::MAX), || {
+        checks += 1;
+        Ok(checks > 1)
+    })?;
+    assert!(reached, "The predicate was not reached after the signal");
+    assert_eq!(checks, 2, "The predicate was not checked after the wakeup");
+    event.delete()
+}
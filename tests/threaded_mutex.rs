@@ -47,7 +47,7 @@ fn test_mutex_locking(instance: NtSync) -> Result<(), Error> {
     let owner = OwnerId::random();
     trace!("My owner: {} other owner: {}", owner, thread_data.2);
     match instance.wait_all(hash!(mutex.into()), Some(SystemTime::now() + Duration::from_millis(200)), Some(owner), NtSyncFlags::empty(), None) {
-        Err(Error::Timeout) => {},
+        Err(error) if matches!(error.kind(), Error::Timeout) => {},
         Err(error) => return Err(error),
         Ok(status) => {
             panic!("this shouldn't happen: {status:?}")
@@ -55,3 +55,95 @@ fn test_mutex_locking(instance: NtSync) -> Result<(), Error> {
     }
     Ok(())
 }
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn lock_as_current_thread(instance: NtSync) -> Result<(), Error> {
+    let mutex = instance.new_mutex()?;
+    instance.wait_all_as_current_thread(hash!(mutex.into()), None, NtSyncFlags::empty(), None)?;
+    let thread = Builder::new()
+        .name("other thread".to_owned())
+        .spawn(move || (OwnerId::current_thread(), mutex.unlock(OwnerId::current_thread())))
+        .map_err(Error::IOError)?;
+    match thread.join() {
+        Ok((other, result)) => {
+            assert_eq!(
+                result,
+                Err(Error::WrongOwner {
+                    expected: other,
+                    actual: Some(OwnerId::current_thread()),
+                }),
+                "Another thread unlocked the mutex"
+            )
+        },
+        Err(error) => panic!("The thread panicked: {error:?}"),
+    }
+    mutex.unlock(OwnerId::current_thread())?;
+    mutex.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn lock_ordered_no_deadlock(instance: NtSync) -> Result<(), Error> {
+    let (first, second) = (instance.new_mutex()?, instance.new_mutex()?);
+    let threads: Vec<JoinHandle<Result<(), Error>>> = [
+        [
+            first, second,
+        ],
+        [
+            second, first,
+        ],
+    ]
+    .into_iter()
+    .map(|order| {
+        let instance = instance.clone();
+        match Builder::new().name("ordered lock thread".to_owned()).spawn::<_, Result<(), Error>>(move || {
+            let owner = OwnerId::random();
+            for _ in 0..200 {
+                let guard = instance.lock_ordered(&order, owner, Some(SystemTime::now() + Duration::from_secs(5)))?;
+                assert_eq!(guard.mutexes().len(), 2);
+                assert!(guard.mutexes()[0] < guard.mutexes()[1], "The mutexes were not locked in order");
+            }
+            Ok(())
+        }) {
+            Ok(join) => join,
+            Err(error) => panic!("Failed to spawn thread for the test: {error}"),
+        }
+    })
+    .collect();
+    for thread in threads {
+        thread.join().expect("an lock thread panicked")?;
+    }
+    assert_eq!(first.read()?.owner(), None);
+    assert_eq!(second.read()?.owner(), None);
+    first.delete()?;
+    second.delete()
+}
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn lock_ordered_keeps_owner_dead(instance: NtSync) -> Result<(), Error> {
+    let (first, second) = (instance.new_mutex()?, instance.new_mutex()?);
+    let (dead, owner, next) = (OwnerId::new(1), OwnerId::new(2), OwnerId::new(3));
+    let abandoned = first.max(second);
+    instance.wait_any(hash!(abandoned.into()), None, Some(dead), NtSyncFlags::empty(), None)?;
+    abandoned.kill(dead)?;
+    assert_eq!(
+        instance
+            .lock_ordered(
+                &[
+                    first, second
+                ],
+                owner,
+                None
+            )
+            .err(),
+        Some(Error::OwnerDead)
+    );
+    assert_eq!(first.min(second).read()?.owner(), None, "The rollback did not unlock the first mutex");
+    assert_eq!(instance.lock_mutex(abandoned, next, None).err(), Some(Error::OwnerDead), "The failed lock_ordered hid that the previous owner died");
+    abandoned.unlock(next)?;
+    first.delete()?;
+    second.delete()
+}
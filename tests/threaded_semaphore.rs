@@ -6,9 +6,13 @@ use ntsync::{
     NtSyncFlags,
 };
 use rstest::rstest;
-use std::thread::{
-    Builder,
-    JoinHandle,
+use std::{
+    panic,
+    thread::{
+        Builder,
+        JoinHandle,
+    },
+    time::UNIX_EPOCH,
 };
 use test_log::test;
 
@@ -32,3 +36,46 @@ fn test_semaphore_locking(instance: NtSync) -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test(rstest)]
+#[cfg(semaphore)]
+fn binary_lock(instance: NtSync) -> Result<(), Error> {
+    let lock = instance.new_binary_lock()?;
+    let guard = instance.acquire_binary_lock(lock, None)?;
+    let second = instance.acquire_binary_lock(lock, Some(UNIX_EPOCH));
+    assert_eq!(second.map(|_| ()), Err(Error::Timeout), "The second acquire did not block");
+    drop(guard);
+    let guard = instance.acquire_binary_lock(lock, Some(UNIX_EPOCH))?;
+    drop(guard);
+    assert_eq!(lock.release(1), Err(Error::SemaphoreOverflow), "The free lock was released again");
+    lock.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(semaphore)]
+fn acquire_peek(instance: NtSync) -> Result<(), Error> {
+    let semaphore = instance.new_semaphore(2)?;
+    assert_eq!(semaphore.acquire_peek(&instance, None, || (false, 7))?, 7, "The result of the closure was lost");
+    assert_eq!(semaphore.read()?.count, 2, "The declined permit was not released");
+    semaphore.acquire_peek(&instance, None, || (true, ()))?;
+    assert_eq!(semaphore.read()?.count, 1, "The consumed permit was released");
+    let result = panic::catch_unwind(|| semaphore.acquire_peek(&instance, None, || -> (bool, ()) { panic!("panic while peeking") }));
+    assert!(result.is_err(), "The panic of the closure was lost");
+    assert_eq!(semaphore.read()?.count, 1, "The permit was not released after the panic");
+    semaphore.release(1)?;
+    semaphore.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(semaphore)]
+fn with_permit_releases_on_panic(instance: NtSync) -> Result<(), Error> {
+    let semaphore = instance.new_semaphore(2)?;
+    assert_eq!(semaphore.with_permit(&instance, None, || semaphore.read().map(|status| status.count))??, 1, "The closure did not run under an permit");
+    let result = panic::catch_unwind(|| semaphore.with_permit(&instance, None, || panic!("panic under an permit")));
+    assert!(result.is_err(), "The panic of the closure was lost");
+    assert_eq!(semaphore.read()?.count, 2, "The permit was not released after the panic");
+    semaphore.delete()?;
+    Ok(())
+}
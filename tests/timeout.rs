@@ -0,0 +1,140 @@
+use ntsync::{
+    Error,
+    IntoTimeout,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    RawTimeout,
+};
+use rstest::rstest;
+use std::{
+    collections::HashSet,
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+/// Reads the monotonic clock in nanoseconds, like the kernel does for waits without [NtSyncFlags::WaitRealtime].
+fn monotonic_now() -> u64 {
+    let mut now = nix::libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    assert_eq!(unsafe { nix::libc::clock_gettime(nix::libc::CLOCK_MONOTONIC, &raw mut now) }, 0, "clock_gettime failed");
+    now.tv_sec as u64 * 1_000_000_000 + now.tv_nsec as u64
+}
+
+#[test(rstest)]
+fn raw_monotonic_timeout(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let offset = Duration::from_millis(50);
+    let start = Instant::now();
+    let result = instance.wait_any_raw_timeout(HashSet::from([event.into()]), monotonic_now() + offset.as_nanos() as u64, None, NtSyncFlags::empty(), None);
+    let elapsed = start.elapsed();
+    assert_eq!(result.map(|_| ()), Err(Error::Timeout), "The wait did not time out");
+    assert!(elapsed >= offset, "The wait returned after {elapsed:?}, before the timeout");
+    assert!(elapsed < Duration::from_secs(5), "The wait took {elapsed:?}, the timeout was ignored");
+    event.delete()?;
+    Ok(())
+}
+
+/// Waits with an relative timeout and checks that the deadline was computed on the clock the flags select.
+fn relative_timeout(instance: &NtSync, flags: NtSyncFlags) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let timeout = Duration::from_millis(50);
+    let start = Instant::now();
+    let result = instance.wait_any_for(HashSet::from([event.into()]), Some(timeout), None, flags, None);
+    let elapsed = start.elapsed();
+    assert_eq!(result.map(|_| ()), Err(Error::Timeout), "The wait did not time out");
+    assert!(elapsed >= timeout, "The wait returned after {elapsed:?}, the deadline was computed on the wrong clock");
+    assert!(elapsed < Duration::from_secs(5), "The wait took {elapsed:?}, the deadline was computed on the wrong clock");
+    event.delete()
+}
+
+#[test(rstest)]
+fn system_time_monotonic_timeout(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let timeout = Duration::from_millis(50);
+    let start = Instant::now();
+    let result = instance.wait_any(HashSet::from([event.into()]), Some(SystemTime::now() + timeout), None, NtSyncFlags::empty(), None);
+    let elapsed = start.elapsed();
+    assert_eq!(result.map(|_| ()), Err(Error::Timeout), "The wait did not time out");
+    assert!(elapsed < Duration::from_secs(5), "The wait took {elapsed:?}, the SystemTime was compared with the monotonic clock");
+    event.delete()
+}
+
+#[test(rstest)]
+fn relative_monotonic_timeout(instance: NtSync) -> Result<(), Error> {
+    relative_timeout(&instance, NtSyncFlags::empty())
+}
+
+#[test(rstest)]
+fn relative_realtime_timeout(instance: NtSync) -> Result<(), Error> {
+    relative_timeout(&instance, NtSyncFlags::WaitRealtime)
+}
+
+/// Waits on an unsignaled event with an timeout that already passed, so the wait has to time out immediately.
+fn expired_timeout(instance: &NtSync, timeout: impl IntoTimeout, realtime: bool) -> Result<(), Error> {
+    let flags = if realtime {
+        NtSyncFlags::WaitRealtime
+    } else {
+        NtSyncFlags::empty()
+    };
+    let event = instance.new_event(false, false)?;
+    let start = Instant::now();
+    let result = instance.wait_any_with(HashSet::from([event.into()]), timeout, None, flags, None);
+    assert_eq!(result.map(|_| ()), Err(Error::Timeout), "The wait did not time out");
+    assert!(start.elapsed() < Duration::from_secs(5), "The expired timeout was converted wrong");
+    event.delete()
+}
+
+#[test(rstest)]
+fn generic_timeouts(instance: NtSync) -> Result<(), Error> {
+    for realtime in [
+        false, true,
+    ] {
+        expired_timeout(&instance, Some(UNIX_EPOCH), realtime)?;
+        expired_timeout(&instance, Some(SystemTime::now()), realtime)?;
+        expired_timeout(&instance, Some(Instant::now()), realtime)?;
+        expired_timeout(&instance, Some(Duration::ZERO), realtime)?;
+        expired_timeout(&instance, RawTimeout(0), realtime)?;
+    }
+    Ok(())
+}
+
+#[test(rstest)]
+fn relative_wait_all_timeout(instance: NtSync) -> Result<(), Error> {
+    let signaled = instance.new_event(true, true)?;
+    let unsignaled = instance.new_event(false, false)?;
+    let timeout = Duration::from_millis(50);
+    for flags in [
+        NtSyncFlags::empty(),
+        NtSyncFlags::WaitRealtime,
+    ] {
+        let start = Instant::now();
+        let result = instance.wait_all_for(
+            HashSet::from([
+                signaled.into(),
+                unsignaled.into(),
+            ]),
+            Some(timeout),
+            None,
+            flags,
+            None,
+        );
+        let elapsed = start.elapsed();
+        assert_eq!(result.map(|_| ()), Err(Error::Timeout), "The wait did not time out");
+        assert!(elapsed >= timeout, "The wait returned after {elapsed:?}, the deadline was computed on the wrong clock");
+        assert!(elapsed < Duration::from_secs(5), "The wait took {elapsed:?}, the deadline was computed on the wrong clock");
+    }
+    assert!(signaled.status()?.signaled(), "The timed out wait_all_for consumed the signaled event");
+    signaled.delete()?;
+    unsignaled.delete()
+}
@@ -0,0 +1,49 @@
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+};
+use rstest::rstest;
+use std::{
+    collections::HashSet,
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn sub_second_timeout(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    for flags in [
+        NtSyncFlags::WaitRealtime,
+        NtSyncFlags::empty(),
+    ] {
+        let start = Instant::now();
+        let result = instance.wait_any(HashSet::from([event.into()]), Some(SystemTime::now() + Duration::from_millis(200)), None, flags, None);
+        let elapsed = start.elapsed();
+        assert_eq!(result.err(), Some(Error::Timeout));
+        assert!(elapsed >= Duration::from_millis(190), "The wait timed out after {elapsed:?}, before the timeout");
+        assert!(elapsed < Duration::from_millis(900), "The wait timed out after {elapsed:?}, the timeout was rounded");
+    }
+    event.delete()
+}
+
+#[test(rstest)]
+fn saturated_timeout(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let alert = instance.new_event(true, true)?;
+    // Too far in the future for u64 nanoseconds, so it has to saturate to an infinite wait instead of wrapping into the past.
+    let far = UNIX_EPOCH.checked_add(Duration::from_secs(1 << 40)).expect("The far timeout does not fit into SystemTime");
+    let status = instance.wait_any(HashSet::from([event.into()]), Some(far), None, NtSyncFlags::WaitRealtime, Some(alert))?;
+    assert!(status.alerted, "The far timeout did not wait for the alert");
+    event.delete()?;
+    alert.delete()
+}
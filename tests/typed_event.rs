@@ -0,0 +1,7 @@
+//! Checks at compile time that an [ntsync::AutoEvent] can not be reset.
+
+#[test]
+fn auto_event_has_no_reset() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/compile_fail/auto_event_reset.rs");
+}
@@ -0,0 +1,71 @@
+#[cfg(mutex)]
+use ntsync::OwnerId;
+use ntsync::{
+    Error,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    WaitOutcome,
+};
+use rstest::rstest;
+use std::time::UNIX_EPOCH;
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn acquired(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(true, false)?;
+    match instance.wait_any2(hash!(event.into()), None, None, NtSyncFlags::empty(), None)? {
+        WaitOutcome::Acquired(status) => assert_eq!(status.index, 0),
+        other => panic!("wait_any2 returned {other:?}"),
+    }
+    event.signal()?;
+    assert!(matches!(instance.wait_all2(hash!(event.into()), None, None, NtSyncFlags::empty(), None)?, WaitOutcome::Acquired(_)));
+    event.delete()
+}
+
+#[test(rstest)]
+fn timed_out(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    assert!(matches!(instance.wait_any2(hash!(event.into()), Some(UNIX_EPOCH), None, NtSyncFlags::WaitRealtime, None)?, WaitOutcome::TimedOut));
+    assert!(matches!(instance.wait_all2(hash!(event.into()), Some(UNIX_EPOCH), None, NtSyncFlags::WaitRealtime, None)?, WaitOutcome::TimedOut));
+    event.delete()
+}
+
+#[test(rstest)]
+fn alerted(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let alert = instance.new_event(true, true)?;
+    assert!(matches!(instance.wait_any2(hash!(event.into()), None, None, NtSyncFlags::empty(), Some(alert))?, WaitOutcome::Alerted));
+    assert!(matches!(instance.wait_all2(hash!(event.into()), None, None, NtSyncFlags::empty(), Some(alert))?, WaitOutcome::Alerted));
+    event.delete()?;
+    alert.delete()
+}
+
+#[test(rstest)]
+#[cfg(mutex)]
+fn abandoned(instance: NtSync) -> Result<(), Error> {
+    let (dead, alive) = (OwnerId::new(1), OwnerId::new(2));
+    let mutex = instance.new_mutex_owned(dead, 1)?;
+    mutex.kill(dead)?;
+    match instance.wait_any2(hash!(mutex.into()), None, Some(alive), NtSyncFlags::empty(), None)? {
+        WaitOutcome::Abandoned(status) => assert_eq!(status.objects()[status.index as usize], mutex.into()),
+        other => panic!("wait_any2 returned {other:?}"),
+    }
+    mutex.kill(alive)?;
+    assert!(matches!(instance.wait_all2(hash!(mutex.into()), None, Some(dead), NtSyncFlags::empty(), None)?, WaitOutcome::Abandoned(_)));
+    assert_eq!(mutex.read()?.owner(), Some(dead));
+    mutex.delete()
+}
+
+#[test(rstest)]
+fn failure(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    event.delete()?;
+    let error = instance.wait_any2(hash!(event.into()), Some(UNIX_EPOCH), None, NtSyncFlags::WaitRealtime, None).err();
+    assert_eq!(error.as_ref().and_then(Error::operation), Some("wait_any"));
+    assert_eq!(error, Some(Error::InvalidValue));
+    Ok(())
+}
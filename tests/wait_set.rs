@@ -0,0 +1,188 @@
+use ntsync::{
+    Error,
+    EventSources,
+    MAX_WAIT_COUNT,
+    NTSyncObjects as _,
+    NtSync,
+    NtSyncFlags,
+    WaitSetProblem,
+};
+use rstest::rstest;
+use std::{
+    collections::HashSet,
+    time::UNIX_EPOCH,
+};
+use test_log::test;
+
+mod fixtures;
+use fixtures::*;
+
+#[test(rstest)]
+fn empty_wait_set(instance: NtSync) -> Result<(), Error> {
+    let result = instance.wait_all(HashSet::new(), None, None, NtSyncFlags::empty(), None);
+    assert_eq!(result.map(|_| ()), Err(Error::EmptyWaitSet), "wait_all accepted an empty set");
+    let result = instance.wait_any(HashSet::new(), None, None, NtSyncFlags::empty(), None);
+    assert_eq!(result.map(|_| ()), Err(Error::EmptyWaitSet), "wait_any accepted an empty set");
+    Ok(())
+}
+
+#[test(rstest)]
+fn validate_deleted_object(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(false, false)?;
+    let deleted = instance.new_event(false, false)?;
+    deleted.delete()?;
+    let sources = HashSet::from([
+        event.into(),
+        deleted.into(),
+    ]);
+    assert_eq!(instance.validate_wait_set(&sources, None, None), Err(vec![WaitSetProblem::Closed(deleted.into())]), "The deleted event was not reported");
+    event.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+fn wait_first_event(instance: NtSync) -> Result<(), Error> {
+    let events = [
+        instance.new_event(false, false)?,
+        instance.new_event(false, false)?,
+        instance.new_event(false, false)?,
+    ];
+    events[1].signal()?;
+    assert_eq!(instance.wait_first_event(&events, None)?, Some(events[1]), "The wrong event was returned");
+    assert_eq!(instance.wait_first_event(&events, Some(UNIX_EPOCH))?, None, "The wait did not time out");
+    for event in events {
+        event.delete()?;
+    }
+    Ok(())
+}
+
+#[test(rstest)]
+fn wait_first_event_duplicates(instance: NtSync) -> Result<(), Error> {
+    let (unsignaled, signaled) = (instance.new_event(false, false)?, instance.new_event(true, true)?);
+    assert_eq!(
+        instance.wait_first_event(
+            &[
+                unsignaled, signaled, signaled
+            ],
+            None
+        )?,
+        Some(signaled),
+        "The duplicate event was rejected"
+    );
+    let result = instance.wait_first_event(&vec![signaled; MAX_WAIT_COUNT + 1], None);
+    assert_eq!(result, Err(Error::InvalidValue), "More events than the kernel accepts were passed on");
+    unsignaled.delete()?;
+    signaled.delete()
+}
+
+/// The wait functions check the count and index of the ioctl arguments with `debug_assert`, so this panics if their computation regresses.
+#[test(rstest)]
+#[cfg(debug_assertions)]
+fn wait_argument_invariants(instance: NtSync) -> Result<(), Error> {
+    let events = [
+        instance.new_event(false, false)?,
+        instance.new_event(false, false)?,
+        instance.new_event(true, false)?,
+    ];
+    let alert = instance.new_event(false, true)?;
+    let sources: HashSet<_> = events.iter().map(|event| (*event).into()).collect();
+    let status = instance.wait_any(sources.clone(), None, None, NtSyncFlags::empty(), Some(alert))?;
+    assert!((status.index as usize) < status.objects().len(), "The index is out of range");
+    alert.signal()?;
+    let status = instance.wait_all(sources, None, None, NtSyncFlags::empty(), Some(alert))?;
+    assert!(status.alerted, "The alert did not stop the wait");
+    for event in events {
+        event.delete()?;
+    }
+    alert.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+fn timeout_report(instance: NtSync) -> Result<(), Error> {
+    let signaled = instance.new_event(true, true)?;
+    let unsignaled = instance.new_event(false, false)?;
+    match instance.wait_any_diagnostic(HashSet::from([unsignaled.into()]), Some(UNIX_EPOCH), None, NtSyncFlags::WaitRealtime, None)? {
+        Ok(status) => panic!("The wait on an unsignaled event succeeded: {status:?}"),
+        Err(report) => assert_eq!(report.unsatisfied, vec![unsignaled.into()], "The unsignaled event is not reported as unsatisfied"),
+    }
+    let report = instance.timeout_report(
+        &[
+            signaled.into(),
+            unsignaled.into(),
+        ],
+        None,
+    );
+    assert_eq!(report.satisfied_after, vec![signaled.into()], "The signaled event is not reported as satisfied");
+    assert_eq!(report.unsatisfied, vec![unsignaled.into()], "The unsignaled event is not reported as unsatisfied");
+    signaled.delete()?;
+    unsignaled.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+fn wait_any_unbounded(instance: NtSync) -> Result<(), Error> {
+    let events = (0..100).map(|_| instance.new_event(false, false)).collect::<Result<Vec<_>, _>>()?;
+    let fired = events[77];
+    fired.signal()?;
+    let status = instance.wait_any_unbounded(events.iter().map(Into::into).collect(), None, None, NtSyncFlags::empty())?;
+    assert_eq!(status.objects().len(), events.len(), "Not all objects are part of the status");
+    assert_eq!(status.objects()[status.index as usize], fired.into(), "The wrong event was reported");
+    for event in events {
+        event.delete()?;
+    }
+    Ok(())
+}
+
+#[test(rstest)]
+fn stable_object_order(instance: NtSync) -> Result<(), Error> {
+    let events = (0..8).map(|_| instance.new_event(true, true)).collect::<Result<Vec<_>, _>>()?;
+    let sources: HashSet<EventSources> = events.iter().map(Into::into).collect();
+    let first = instance.wait_all(sources.clone(), None, None, NtSyncFlags::empty(), None)?;
+    let second = instance.wait_all(sources.clone(), None, None, NtSyncFlags::empty(), None)?;
+    assert_eq!(first.objects().len(), sources.len(), "Not all objects are part of the status");
+    assert_eq!(first.objects(), second.objects(), "The order of the objects changed between identical waits");
+    let any = instance.wait_any(sources, None, None, NtSyncFlags::empty(), None)?;
+    assert_eq!(any.objects(), first.objects(), "wait_any and wait_all use different orders");
+    for event in events {
+        event.delete()?;
+    }
+    Ok(())
+}
+
+#[test(rstest)]
+#[cfg(semaphore)]
+fn ready_subset(instance: NtSync) -> Result<(), Error> {
+    let event = instance.new_event(true, false)?;
+    let semaphore = instance.new_semaphore(1)?;
+    instance.wait_any(HashSet::from([semaphore.into()]), None, None, NtSyncFlags::empty(), None)?;
+    let ready = instance.ready_subset(
+        &HashSet::from([
+            event.into(),
+            semaphore.into(),
+        ]),
+        None,
+    )?;
+    assert_eq!(ready, HashSet::from([EventSources::Event(event)]), "The ready subset is wrong");
+    assert!(event.status()?.signaled(), "The query consumed the signal of the auto event");
+    event.delete()?;
+    semaphore.delete()?;
+    Ok(())
+}
+
+#[test(rstest)]
+fn wait_any_triggered(instance: NtSync) -> Result<(), Error> {
+    let events = (0..4).map(|_| instance.new_event(false, false)).collect::<Result<Vec<_>, _>>()?;
+    let alert = instance.new_event(false, false)?;
+    events[2].signal()?;
+    let status = instance.wait_any(events.iter().map(Into::into).collect(), None, None, NtSyncFlags::empty(), Some(alert))?;
+    assert_eq!(status.triggered(), Some(&events[2].into()), "The wrong event was reported");
+    alert.signal()?;
+    let status = instance.wait_any(events.iter().map(Into::into).collect(), None, None, NtSyncFlags::empty(), Some(alert))?;
+    assert!(status.alerted);
+    assert_eq!(status.triggered(), None, "The alert was reported as object");
+    for event in events {
+        event.delete()?;
+    }
+    alert.delete()
+}